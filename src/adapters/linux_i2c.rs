@@ -0,0 +1,120 @@
+use super::Adapters;
+use crate::{Apds9960I2CPort, RetryableError};
+use async_trait::async_trait;
+use std::{
+    convert::TryFrom,
+    fs::{File, OpenOptions},
+    io,
+    os::unix::io::AsRawFd,
+    path::Path,
+};
+
+const I2C_RDWR: libc::c_ulong = 0x0707;
+const I2C_M_RD: u16 = 0x0001;
+
+#[repr(C)]
+struct I2CMsg {
+    addr: u16,
+    flags: u16,
+    len: u16,
+    buf: *mut u8,
+}
+
+#[repr(C)]
+struct I2CRdwrIoctlData {
+    msgs: *mut I2CMsg,
+    nmsgs: u32,
+}
+
+/// Linux `i2c-dev` adapter.
+///
+/// Performs transactions on a `/dev/i2c-N` character device with `I2C_RDWR`
+/// ioctl.
+pub struct LinuxI2C {
+    file: File,
+}
+
+impl LinuxI2C {
+    /// Opens an I²C character device at `path`.
+    ///
+    /// # Errors
+    ///
+    /// If the device can't be opened, the I/O error is returned.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        Ok(Self { file })
+    }
+
+    /// Opens `/dev/i2c-{bus}` character device.
+    ///
+    /// # Errors
+    ///
+    /// If the device can't be opened, the I/O error is returned.
+    pub fn open_bus(bus: u32) -> io::Result<Self> {
+        Self::open(format!("/dev/i2c-{}", bus))
+    }
+
+    fn transfer(&mut self, addr: u8, tx: &mut [u8], rx: &mut [u8]) -> io::Result<()> {
+        let len = |buf: &[u8]| {
+            u16::try_from(buf.len()).map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))
+        };
+        let mut msgs =
+            [I2CMsg { addr: addr.into(), flags: 0, len: len(tx)?, buf: tx.as_mut_ptr() }, I2CMsg {
+                addr: addr.into(),
+                flags: I2C_M_RD,
+                len: len(rx)?,
+                buf: rx.as_mut_ptr(),
+            }];
+        let mut data =
+            I2CRdwrIoctlData { msgs: msgs.as_mut_ptr(), nmsgs: if rx.is_empty() { 1 } else { 2 } };
+        #[allow(clippy::cast_lossless, clippy::useless_conversion)]
+        let ret = unsafe { libc::ioctl(self.file.as_raw_fd(), I2C_RDWR as _, &mut data) };
+        if ret < 0 { Err(io::Error::last_os_error()) } else { Ok(()) }
+    }
+}
+
+#[async_trait]
+impl Apds9960I2CPort<Adapters> for LinuxI2C {
+    type Error = io::Error;
+
+    async fn write(
+        &mut self,
+        addr: u8,
+        mut buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, io::Error)> {
+        match self.transfer(addr, &mut buf[..count], &mut []) {
+            Ok(()) => Ok(buf),
+            Err(err) => Err((buf, err)),
+        }
+    }
+
+    async fn read(
+        &mut self,
+        addr: u8,
+        mut buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, io::Error)> {
+        let mut reg = [buf[0]];
+        match self.transfer(addr, &mut reg, &mut buf[..count]) {
+            Ok(()) => Ok(buf),
+            Err(err) => Err((buf, err)),
+        }
+    }
+}
+
+impl RetryableError for io::Error {
+    /// Missing acknowledge, lost arbitration, bus timeouts and interrupted
+    /// calls are retryable.
+    fn is_retryable(&self) -> bool {
+        match self.kind() {
+            io::ErrorKind::Interrupted | io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => {
+                true
+            }
+            _ => matches!(
+                self.raw_os_error(),
+                Some(libc::EIO | libc::ENXIO | libc::EAGAIN | libc::ETIMEDOUT | libc::EREMOTEIO)
+            ),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! Ready-made port implementations.
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+mod linux_i2c;
+mod mux;
+mod retry;
+mod shared_bus;
+#[cfg(feature = "std")]
+mod sim;
+#[cfg(feature = "smartoris-i2c")]
+mod smartoris_i2c;
+#[cfg(feature = "std")]
+mod std_delay;
+mod trace;
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub use self::linux_i2c::LinuxI2C;
+pub use self::{
+    mux::{I2CMux, MuxChannel},
+    retry::{Retry, RetryPolicy, RetryStats},
+    shared_bus::{SharedBus, SharedBusGuard},
+    trace::{Operation, Trace, Tracer, Transaction},
+};
+#[cfg(feature = "std")]
+pub use self::{
+    sim::{Apds9960Sim, SimError},
+    std_delay::StdDelay,
+};
+
+/// A marker type for port implementations in this module.
+pub struct Adapters;
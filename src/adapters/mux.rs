@@ -0,0 +1,121 @@
+use crate::Apds9960I2CPort;
+use async_trait::async_trait;
+use core::mem::take;
+
+/// Number of channels of a TCA9548A multiplexer.
+const CHANNELS: u8 = 8;
+
+/// TCA9548A/PCA9548A-style I²C multiplexer.
+///
+/// The multiplexer has a single control register: every bit of it connects
+/// the corresponding downstream channel to the upstream bus. The adapter
+/// keeps one channel connected at a time and remembers the active channel,
+/// so consecutive transactions on the same channel don't reselect it.
+pub struct I2CMux<P> {
+    bus: P,
+    addr: u8,
+    active: Option<u8>,
+    buf: Box<[u8]>,
+}
+
+/// A multiplexer channel, implementing [`Apds9960I2CPort`].
+///
+/// Obtained with [`I2CMux::channel`].
+pub struct MuxChannel<'a, P> {
+    mux: &'a mut I2CMux<P>,
+    channel: u8,
+}
+
+impl<P> I2CMux<P> {
+    /// Default multiplexer slave address.
+    pub const DEFAULT_ADDR: u8 = 0x70;
+
+    /// Creates a multiplexer adapter for `bus` with the multiplexer at `addr`
+    /// slave address.
+    ///
+    /// The active channel is unknown until the first transaction.
+    pub fn new(bus: P, addr: u8) -> Self {
+        Self { bus, addr, active: None, buf: vec![0; 1].into_boxed_slice() }
+    }
+
+    /// Returns a port for `channel`.
+    ///
+    /// # Panics
+    ///
+    /// If `channel` is not in `0..8`.
+    pub fn channel(&mut self, channel: u8) -> MuxChannel<'_, P> {
+        assert!(channel < CHANNELS, "invalid multiplexer channel {}", channel);
+        MuxChannel { mux: self, channel }
+    }
+
+    /// Returns the currently selected channel, if known.
+    pub fn active(&self) -> Option<u8> {
+        self.active
+    }
+
+    /// Releases the underlying bus.
+    pub fn release(self) -> P {
+        self.bus
+    }
+
+    /// Connects `channel` to the bus unless it's already active.
+    ///
+    /// # Errors
+    ///
+    /// If `bus` implementation returns `Err`, it's propagated to the caller.
+    pub async fn select<A>(&mut self, channel: u8) -> Result<(), P::Error>
+    where
+        P: Apds9960I2CPort<A>,
+    {
+        if self.active == Some(channel) {
+            return Ok(());
+        }
+        let mut buf = take(&mut self.buf);
+        buf[0] = 1 << channel;
+        match self.bus.write(self.addr, buf, 1).await {
+            Ok(buf) => {
+                self.buf = buf;
+                self.active = Some(channel);
+                Ok(())
+            }
+            Err((buf, err)) => {
+                self.buf = buf;
+                self.active = None;
+                Err(err)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<A, P: Apds9960I2CPort<A> + Send> Apds9960I2CPort<A> for MuxChannel<'_, P> {
+    type Error = P::Error;
+
+    async fn write(
+        &mut self,
+        addr: u8,
+        buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, P::Error)> {
+        if let Err(err) = self.mux.select(self.channel).await {
+            return Err((buf, err));
+        }
+        self.mux.bus.write(addr, buf, count).await
+    }
+
+    async fn read(
+        &mut self,
+        addr: u8,
+        buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, P::Error)> {
+        if let Err(err) = self.mux.select(self.channel).await {
+            return Err((buf, err));
+        }
+        self.mux.bus.read(addr, buf, count).await
+    }
+
+    fn take_retries(&mut self) -> u32 {
+        self.mux.bus.take_retries()
+    }
+}
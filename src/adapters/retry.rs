@@ -0,0 +1,225 @@
+use crate::{Apds9960DelayPort, Apds9960I2CPort, RetryableError};
+use async_trait::async_trait;
+use core::{mem, time::Duration};
+
+/// `GFIFO_U` register address.
+const GFIFO: u8 = 0xFC;
+
+/// Retry settings for [`Retry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts per transaction, including the first one.
+    pub attempts: u32,
+    /// Delay before the first retry. Doubled before each subsequent retry.
+    pub backoff: Duration,
+    /// Upper limit for the delay between retries.
+    pub max_backoff: Duration,
+    /// Whether gesture FIFO reads are retried.
+    ///
+    /// A FIFO read that fails in the middle of the data phase may have
+    /// already popped some datasets. The repeated read returns the following
+    /// datasets, so the popped ones are silently lost. A failure in the
+    /// address phase loses nothing. Disable to get every FIFO read failure
+    /// reported to the caller instead.
+    pub retry_fifo_reads: bool,
+}
+
+/// Retry counters of [`Retry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RetryStats {
+    /// Number of repeated transactions.
+    pub retries: u32,
+    /// Number of transactions that succeeded after one or more retries.
+    pub recovered: u32,
+    /// Number of transactions that failed with a retryable error after all
+    /// attempts.
+    pub exhausted: u32,
+    /// Number of transactions that failed with a fatal error.
+    pub fatal: u32,
+    /// Number of failed gesture FIFO read attempts, retried or not. Each of
+    /// them may have lost datasets.
+    pub fifo_failures: u32,
+}
+
+/// A port wrapper that repeats failed transactions.
+///
+/// Transactions failed with an error classified as retryable by
+/// [`RetryableError`] are repeated after a backoff delay, up to
+/// [`RetryPolicy::attempts`] times. Since every driver method goes through the
+/// port, this covers register reads and writes, including bursts.
+///
+/// A write is repeated as a whole, which is safe for the APDS-9960 registers.
+/// Gesture FIFO drains are retried as well unless
+/// [`RetryPolicy::retry_fifo_reads`] is cleared, see there for the possible
+/// data loss. Every failed FIFO read is counted in
+/// [`RetryStats::fifo_failures`].
+///
+/// Retries are also reported to the driver through
+/// [`Apds9960I2CPort::take_retries`], and counted in
+/// [`BusStats::retries`](crate::BusStats::retries) when the driver collects
+/// stats.
+///
+/// ```
+/// # use smartoris_apds9960::{
+/// #     adapters::{Retry, RetryPolicy},
+/// #     Apds9960DelayPort, Apds9960Drv, Apds9960I2CPort, RetryableError,
+/// # };
+/// # async fn f<P, D>(bus: P, delay: D) -> Result<(), P::Error>
+/// # where
+/// #     P: Apds9960I2CPort<()> + Send,
+/// #     P::Error: RetryableError + Send,
+/// #     D: Apds9960DelayPort<()> + Send,
+/// # {
+/// let mut i2c = Retry::new(bus, delay, RetryPolicy::default());
+/// let mut apds9960 = Apds9960Drv::<()>::init();
+/// apds9960.load_id(&mut i2c).await?;
+/// let retries = i2c.stats().retries;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Retry<P, D> {
+    port: P,
+    delay: D,
+    policy: RetryPolicy,
+    stats: RetryStats,
+    pending: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(16),
+            retry_fifo_reads: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns the delay before `retry`-th retry, counting from 1.
+    #[must_use]
+    pub fn backoff(self, retry: u32) -> Duration {
+        let factor = 1_u32.checked_shl(retry.saturating_sub(1)).unwrap_or(u32::MAX);
+        self.backoff.checked_mul(factor).map_or(self.max_backoff, |d| d.min(self.max_backoff))
+    }
+}
+
+impl<P, D> Retry<P, D> {
+    /// Wraps `port`, using `delay` for the backoff.
+    pub fn new(port: P, delay: D, policy: RetryPolicy) -> Self {
+        Self { port, delay, policy, stats: RetryStats::default(), pending: 0 }
+    }
+
+    /// Returns the current retry settings.
+    pub fn policy(&self) -> RetryPolicy {
+        self.policy
+    }
+
+    /// Replaces the retry settings.
+    pub fn set_policy(&mut self, policy: RetryPolicy) {
+        self.policy = policy;
+    }
+
+    /// Returns the retry counters.
+    pub fn stats(&self) -> RetryStats {
+        self.stats
+    }
+
+    /// Resets the retry counters.
+    pub fn reset_stats(&mut self) {
+        self.stats = RetryStats::default();
+    }
+
+    /// Returns the inner port.
+    pub fn port(&mut self) -> &mut P {
+        &mut self.port
+    }
+
+    /// Releases the inner port and the delay.
+    pub fn release(self) -> (P, D) {
+        (self.port, self.delay)
+    }
+
+    async fn transfer<A>(
+        &mut self,
+        addr: u8,
+        mut buf: Box<[u8]>,
+        count: usize,
+        read: bool,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, P::Error)>
+    where
+        P: Apds9960I2CPort<A>,
+        P::Error: RetryableError,
+        D: Apds9960DelayPort<A>,
+    {
+        let fifo = read && buf[0] >= GFIFO;
+        let mut attempt = 1;
+        loop {
+            let result = if read {
+                self.port.read(addr, buf, count).await
+            } else {
+                self.port.write(addr, buf, count).await
+            };
+            if fifo && result.is_err() {
+                self.stats.fifo_failures += 1;
+            }
+            match result {
+                Ok(buf) => {
+                    if attempt > 1 {
+                        self.stats.recovered += 1;
+                    }
+                    return Ok(buf);
+                }
+                Err((_, ref err)) if !err.is_retryable() => {
+                    self.stats.fatal += 1;
+                    return result;
+                }
+                Err(_) if fifo && !self.policy.retry_fifo_reads => return result,
+                Err(_) if attempt >= self.policy.attempts => {
+                    self.stats.exhausted += 1;
+                    return result;
+                }
+                Err((failed, _)) => buf = failed,
+            }
+            self.delay.delay(self.policy.backoff(attempt)).await;
+            self.stats.retries += 1;
+            self.pending += 1;
+            attempt += 1;
+        }
+    }
+}
+
+#[async_trait]
+impl<A, P, D> Apds9960I2CPort<A> for Retry<P, D>
+where
+    P: Apds9960I2CPort<A> + Send,
+    P::Error: RetryableError + Send,
+    D: Apds9960DelayPort<A> + Send,
+{
+    type Error = P::Error;
+
+    async fn write(
+        &mut self,
+        addr: u8,
+        buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, P::Error)> {
+        self.transfer(addr, buf, count, false).await
+    }
+
+    async fn read(
+        &mut self,
+        addr: u8,
+        buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, P::Error)> {
+        self.transfer(addr, buf, count, true).await
+    }
+
+    fn take_retries(&mut self) -> u32 {
+        mem::take(&mut self.pending) + self.port.take_retries()
+    }
+}
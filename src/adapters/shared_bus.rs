@@ -0,0 +1,105 @@
+use crate::{
+    sync::{Mutex, MutexGuard},
+    Apds9960I2CPort,
+};
+use alloc::sync::Arc;
+use async_trait::async_trait;
+use core::ops::{Deref, DerefMut};
+
+/// A handle onto an I²C bus shared between several drivers.
+///
+/// The bus is kept behind an async mutex, and every handle clone locks it for
+/// the duration of a single transaction. The handle implements
+/// [`Apds9960I2CPort`] whenever the bus does, and
+/// [`lock`](SharedBus::lock) gives exclusive access to the bus for other
+/// drivers.
+///
+/// Retries of a port inside the shared bus can't be attributed to a single
+/// driver, so they aren't reported through
+/// [`take_retries`](Apds9960I2CPort::take_retries). Wrap each handle in
+/// [`Retry`](crate::adapters::Retry) instead to count them per driver.
+///
+/// ```
+/// # use smartoris_apds9960::{adapters::SharedBus, Apds9960Drv, Apds9960I2CPort};
+/// # async fn f<P: Apds9960I2CPort<()> + Send>(bus: P) -> Result<(), P::Error> {
+/// let bus = SharedBus::new(bus);
+/// let mut i2c = bus.clone();
+/// let mut apds9960 = Apds9960Drv::<()>::init();
+/// apds9960.load_id(&mut i2c).await?;
+/// let mut other = bus.lock().await;
+/// // Access the bus directly through `other`.
+/// # Ok(())
+/// # }
+/// ```
+pub struct SharedBus<P> {
+    bus: Arc<Mutex<P>>,
+}
+
+/// Exclusive access to a [`SharedBus`], released when dropped.
+pub struct SharedBusGuard<'a, P> {
+    guard: MutexGuard<'a, P>,
+}
+
+impl<P> SharedBus<P> {
+    /// Wraps `bus` for sharing.
+    pub fn new(bus: P) -> Self {
+        Self { bus: Arc::new(Mutex::new(bus)) }
+    }
+
+    /// Waits for exclusive access to the bus.
+    pub async fn lock(&self) -> SharedBusGuard<'_, P> {
+        SharedBusGuard { guard: self.bus.lock().await }
+    }
+
+    /// Returns the bus if this is the last handle.
+    ///
+    /// # Errors
+    ///
+    /// Returns the handle back if other handles exist.
+    pub fn try_into_inner(self) -> Result<P, Self> {
+        Arc::try_unwrap(self.bus).map(Mutex::into_inner).map_err(|bus| Self { bus })
+    }
+}
+
+impl<P> Clone for SharedBus<P> {
+    fn clone(&self) -> Self {
+        Self { bus: Arc::clone(&self.bus) }
+    }
+}
+
+impl<P> Deref for SharedBusGuard<'_, P> {
+    type Target = P;
+
+    fn deref(&self) -> &P {
+        &self.guard
+    }
+}
+
+impl<P> DerefMut for SharedBusGuard<'_, P> {
+    fn deref_mut(&mut self) -> &mut P {
+        &mut self.guard
+    }
+}
+
+#[async_trait]
+impl<A, P: Apds9960I2CPort<A> + Send> Apds9960I2CPort<A> for SharedBus<P> {
+    type Error = P::Error;
+
+    async fn write(
+        &mut self,
+        addr: u8,
+        buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, P::Error)> {
+        self.bus.lock().await.write(addr, buf, count).await
+    }
+
+    async fn read(
+        &mut self,
+        addr: u8,
+        buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, P::Error)> {
+        self.bus.lock().await.read(addr, buf, count).await
+    }
+}
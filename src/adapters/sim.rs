@@ -0,0 +1,241 @@
+use super::Adapters;
+use crate::{Apds9960I2CPort, RetryableError, DEFAULT_ADDR};
+use alloc::collections::VecDeque;
+use async_trait::async_trait;
+use core::fmt;
+
+const DEVICE_ID: u8 = 0xAB;
+const ID: u8 = 0x92;
+const STATUS: u8 = 0x93;
+const CDATA: u8 = 0x94;
+const PDATA: u8 = 0x9C;
+const GCONF4: u8 = 0xAB;
+const GFLVL: u8 = 0xAE;
+const GSTATUS: u8 = 0xAF;
+const IFORCE: u8 = 0xE4;
+const PICLEAR: u8 = 0xE5;
+const CICLEAR: u8 = 0xE6;
+const AICLEAR: u8 = 0xE7;
+const GFIFO: u8 = 0xFC;
+const GFIFO_DEPTH: usize = 32;
+
+const AVALID: u8 = 1 << 0;
+const PVALID: u8 = 1 << 1;
+const GINT: u8 = 1 << 2;
+const AINT: u8 = 1 << 4;
+const PINT: u8 = 1 << 5;
+const PGSAT: u8 = 1 << 6;
+const CPSAT: u8 = 1 << 7;
+const GVALID: u8 = 1 << 0;
+const GFOV: u8 = 1 << 1;
+const GFIFO_CLR: u8 = 1 << 2;
+
+/// In-memory APDS-9960 simulator.
+///
+/// Models the register file, the auto-increment protocol, the interrupt clear
+/// registers and the gesture FIFO. Measurements are not simulated, they are
+/// injected with [`Apds9960Sim::set_rgbc`], [`Apds9960Sim::set_pdata`] and
+/// [`Apds9960Sim::push_gesture`].
+pub struct Apds9960Sim {
+    addr: u8,
+    regs: [u8; 0x100],
+    gfifo: VecDeque<[u8; 4]>,
+    nacks: usize,
+}
+
+/// Simulator I²C error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimError {
+    /// The slave address is not acknowledged.
+    Nack,
+}
+
+impl Apds9960Sim {
+    /// Creates a new simulator with all registers at their reset values.
+    #[must_use]
+    pub fn new() -> Self {
+        let mut regs = [0; 0x100];
+        regs[0x81] = 0xFF;
+        regs[0x83] = 0xFF;
+        regs[0x8D] = 0x60;
+        regs[0x8E] = 0x40;
+        regs[0x90] = 0x01;
+        regs[ID as usize] = DEVICE_ID;
+        regs[0xA6] = 0x40;
+        Self { addr: DEFAULT_ADDR, regs, gfifo: VecDeque::with_capacity(GFIFO_DEPTH), nacks: 0 }
+    }
+
+    /// Changes the I²C slave address the simulator responds to.
+    pub fn set_addr(&mut self, addr: u8) {
+        self.addr = addr;
+    }
+
+    /// Makes the next `count` transactions fail with [`SimError::Nack`].
+    pub fn fail_next(&mut self, count: usize) {
+        self.nacks = count;
+    }
+
+    fn acknowledge(&mut self, addr: u8) -> bool {
+        if self.nacks > 0 {
+            self.nacks -= 1;
+            return false;
+        }
+        addr == self.addr
+    }
+
+    /// Returns the current value of `reg` register.
+    #[must_use]
+    pub fn reg(&self, reg: u8) -> u8 {
+        self.regs[reg as usize]
+    }
+
+    /// Sets `reg` register to `value` bypassing the access rules.
+    pub fn set_reg(&mut self, reg: u8, value: u8) {
+        self.regs[reg as usize] = value;
+    }
+
+    /// Injects an RGBC measurement and sets `Status::avalid`.
+    pub fn set_rgbc(&mut self, c: u16, r: u16, g: u16, b: u16) {
+        for (i, value) in [c, r, g, b].iter().enumerate() {
+            let offset = CDATA as usize + i * 2;
+            self.regs[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+        }
+        self.regs[STATUS as usize] |= AVALID;
+    }
+
+    /// Injects a proximity measurement and sets `Status::pvalid`.
+    pub fn set_pdata(&mut self, pdata: u8) {
+        self.regs[PDATA as usize] = pdata;
+        self.regs[STATUS as usize] |= PVALID;
+    }
+
+    /// Pushes a gesture dataset into the FIFO.
+    ///
+    /// If the FIFO is full, the dataset is dropped and `Gstatus::gfov` is set.
+    pub fn push_gesture(&mut self, dataset: [u8; 4]) {
+        if self.gfifo.len() < GFIFO_DEPTH {
+            self.gfifo.push_back(dataset);
+        } else {
+            self.regs[GSTATUS as usize] |= GFOV;
+        }
+        self.update_gfifo();
+    }
+
+    fn update_gfifo(&mut self) {
+        #[allow(clippy::cast_possible_truncation)]
+        let level = self.gfifo.len() as u8;
+        self.regs[GFLVL as usize] = level;
+        if level > 0 {
+            self.regs[GSTATUS as usize] |= GVALID;
+            self.regs[STATUS as usize] |= GINT;
+        } else {
+            self.regs[GSTATUS as usize] &= !GVALID;
+            self.regs[STATUS as usize] &= !GINT;
+        }
+    }
+
+    fn store(&mut self, reg: u8, value: u8) {
+        match reg {
+            ID | STATUS | CDATA..=PDATA | GFLVL | GSTATUS | GFIFO..=0xFF => {}
+            GCONF4 => {
+                if value & GFIFO_CLR != 0 {
+                    self.gfifo.clear();
+                    self.regs[GSTATUS as usize] = 0;
+                    self.update_gfifo();
+                }
+                self.regs[reg as usize] = value & !GFIFO_CLR;
+            }
+            _ => self.regs[reg as usize] = value,
+        }
+    }
+
+    fn touch(&mut self, reg: u8) {
+        let status = &mut self.regs[STATUS as usize];
+        match reg {
+            IFORCE => *status |= AINT | PINT,
+            PICLEAR => *status &= !(PINT | PGSAT),
+            CICLEAR => *status &= !(AINT | CPSAT),
+            AICLEAR => *status &= !(AINT | PINT | PGSAT | CPSAT),
+            _ => {}
+        }
+    }
+
+    fn load(&mut self, reg: u8, buf: &mut [u8]) {
+        if reg >= GFIFO {
+            for chunk in buf.chunks_mut(4) {
+                let dataset = self.gfifo.pop_front().unwrap_or_default();
+                chunk.copy_from_slice(&dataset[..chunk.len()]);
+            }
+            self.update_gfifo();
+        } else {
+            let mut reg = reg;
+            for byte in buf {
+                *byte = self.regs[reg as usize];
+                reg = reg.wrapping_add(1);
+            }
+        }
+    }
+}
+
+impl Default for Apds9960Sim {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Apds9960I2CPort<Adapters> for Apds9960Sim {
+    type Error = SimError;
+
+    async fn write(
+        &mut self,
+        addr: u8,
+        buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, SimError)> {
+        if !self.acknowledge(addr) {
+            return Err((buf, SimError::Nack));
+        }
+        let reg = buf[0];
+        if count == 1 {
+            self.touch(reg);
+        }
+        for (i, &value) in buf[1..count].iter().enumerate() {
+            #[allow(clippy::cast_possible_truncation)]
+            self.store(reg.wrapping_add(i as u8), value);
+        }
+        Ok(buf)
+    }
+
+    async fn read(
+        &mut self,
+        addr: u8,
+        mut buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, SimError)> {
+        if !self.acknowledge(addr) {
+            return Err((buf, SimError::Nack));
+        }
+        let reg = buf[0];
+        self.load(reg, &mut buf[..count]);
+        Ok(buf)
+    }
+}
+
+impl RetryableError for SimError {
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::Nack => true,
+        }
+    }
+}
+
+impl fmt::Display for SimError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nack => write!(f, "address not acknowledged"),
+        }
+    }
+}
+
+impl std::error::Error for SimError {}
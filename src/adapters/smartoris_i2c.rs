@@ -0,0 +1,43 @@
+use super::Adapters;
+use crate::Apds9960I2CPort;
+use async_trait::async_trait;
+use drone_cortexm::thr::prelude::*;
+use drone_stm32_map::periph::{dma::ch::DmaChMap, i2c::I2CMap};
+use smartoris_i2c_dep::I2CDrv;
+
+/// [`smartoris-i2c`](https://crates.io/crates/smartoris-i2c) adapter.
+///
+/// The I²C driver doesn't report bus errors to the session owner. Errors are
+/// handled by its `I2CEr` interrupt handler, therefore the port error type is
+/// uninhabited.
+#[async_trait]
+impl<
+    I2C: I2CMap,
+    I2CEv: IntToken,
+    I2CEr: IntToken,
+    DmaTx: DmaChMap,
+    DmaTxInt: IntToken,
+    DmaRx: DmaChMap,
+    DmaRxInt: IntToken,
+> Apds9960I2CPort<Adapters> for I2CDrv<I2C, I2CEv, I2CEr, DmaTx, DmaTxInt, DmaRx, DmaRxInt>
+{
+    type Error = !;
+
+    async fn write(
+        &mut self,
+        addr: u8,
+        buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, !)> {
+        Ok(self.master(buf).write(addr, ..count).await.stop())
+    }
+
+    async fn read(
+        &mut self,
+        addr: u8,
+        buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, !)> {
+        Ok(self.master(buf).write(addr, ..1).await.read(addr, ..count).await.stop())
+    }
+}
@@ -0,0 +1,39 @@
+use super::Adapters;
+use crate::Apds9960DelayPort;
+use async_trait::async_trait;
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// [`Apds9960DelayPort`] implementation that blocks the current thread.
+///
+/// Meant for host tools driven by a blocking executor.
+pub struct StdDelay {
+    start: Instant,
+}
+
+impl StdDelay {
+    /// Creates a new delay port.
+    #[must_use]
+    pub fn new() -> Self {
+        Self { start: Instant::now() }
+    }
+}
+
+impl Default for StdDelay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Apds9960DelayPort<Adapters> for StdDelay {
+    async fn delay(&mut self, duration: Duration) {
+        thread::sleep(duration);
+    }
+
+    fn now(&mut self) -> Option<Duration> {
+        Some(self.start.elapsed())
+    }
+}
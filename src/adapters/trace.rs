@@ -0,0 +1,167 @@
+use crate::{reg::RegisterInfo, Apds9960I2CPort};
+use async_trait::async_trait;
+use core::fmt;
+
+const GFIFO: u8 = 0xFC;
+
+/// Kind of a traced driver access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Operation {
+    /// Register read.
+    Load,
+    /// Register write.
+    Store,
+    /// Address-only access to a special function register.
+    Touch,
+    /// Gesture FIFO page read.
+    DrainFifo,
+}
+
+/// A single driver access observed by [`Trace`].
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Transaction<'a> {
+    /// Kind of the access.
+    pub op: Operation,
+    /// The first register address.
+    pub reg: u8,
+    /// The first register name as in the datasheet, if the address is known.
+    pub name: Option<&'static str>,
+    /// Written bytes, or read bytes if the transaction succeeded.
+    pub bytes: &'a [u8],
+    /// `true` if the port returned `Ok`.
+    pub ok: bool,
+}
+
+/// Receiver of [`Transaction`]s.
+///
+/// Implemented for closures, so a tracer can forward transactions to any
+/// logging facility. [`Transaction`] implements [`fmt::Display`] for `log`,
+/// and `defmt::Format` when `defmt` feature is enabled:
+///
+/// ```
+/// # use smartoris_apds9960::{adapters::{Trace, Transaction}, Apds9960I2CPort};
+/// # fn f<P: Apds9960I2CPort<()>>(bus: P) {
+/// let i2c = Trace::new(bus, |transaction: &Transaction<'_>| {
+///     println!("{}", transaction);
+/// });
+/// # }
+/// ```
+pub trait Tracer {
+    /// Called after every transaction.
+    fn trace(&mut self, transaction: &Transaction<'_>);
+}
+
+impl<F: FnMut(&Transaction<'_>)> Tracer for F {
+    fn trace(&mut self, transaction: &Transaction<'_>) {
+        self(transaction);
+    }
+}
+
+/// A port wrapper that reports every transaction to a [`Tracer`].
+///
+/// Transactions are decoded back into driver accesses: a write of a single
+/// byte is a [`Operation::Touch`], a read from the gesture FIFO is a
+/// [`Operation::DrainFifo`].
+pub struct Trace<P, T> {
+    port: P,
+    tracer: T,
+}
+
+impl<P, T> Trace<P, T> {
+    /// Wraps `port`, reporting transactions to `tracer`.
+    pub fn new(port: P, tracer: T) -> Self {
+        Self { port, tracer }
+    }
+
+    /// Returns the inner port.
+    pub fn port(&mut self) -> &mut P {
+        &mut self.port
+    }
+
+    /// Returns the tracer.
+    pub fn tracer(&mut self) -> &mut T {
+        &mut self.tracer
+    }
+
+    /// Releases the inner port and the tracer.
+    pub fn release(self) -> (P, T) {
+        (self.port, self.tracer)
+    }
+}
+
+impl<'a> Transaction<'a> {
+    fn new(op: Operation, reg: u8, bytes: &'a [u8], ok: bool) -> Self {
+        Self { op, reg, name: RegisterInfo::by_addr(reg).map(RegisterInfo::name), bytes, ok }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Load => write!(f, "load"),
+            Self::Store => write!(f, "store"),
+            Self::Touch => write!(f, "touch"),
+            Self::DrainFifo => write!(f, "drain"),
+        }
+    }
+}
+
+impl fmt::Display for Transaction<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} (0x{:02X})", self.op, self.name.unwrap_or("?"), self.reg)?;
+        if !self.bytes.is_empty() {
+            write!(f, " {:02X?}", self.bytes)?;
+        }
+        if !self.ok {
+            write!(f, " failed")?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<A, P, T> Apds9960I2CPort<A> for Trace<P, T>
+where
+    P: Apds9960I2CPort<A> + Send,
+    T: Tracer + Send,
+{
+    type Error = P::Error;
+
+    async fn write(
+        &mut self,
+        addr: u8,
+        buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, P::Error)> {
+        let result = self.port.write(addr, buf, count).await;
+        let (buf, ok) = match &result {
+            Ok(buf) | Err((buf, _)) => (buf, result.is_ok()),
+        };
+        let op = if count == 1 { Operation::Touch } else { Operation::Store };
+        self.tracer.trace(&Transaction::new(op, buf[0], &buf[1..count], ok));
+        result
+    }
+
+    async fn read(
+        &mut self,
+        addr: u8,
+        buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, P::Error)> {
+        let reg = buf[0];
+        let result = self.port.read(addr, buf, count).await;
+        let op = if reg >= GFIFO { Operation::DrainFifo } else { Operation::Load };
+        let transaction = match &result {
+            Ok(buf) => Transaction::new(op, reg, &buf[..count], true),
+            Err(_) => Transaction::new(op, reg, &[], false),
+        };
+        self.tracer.trace(&transaction);
+        result
+    }
+
+    fn take_retries(&mut self) -> u32 {
+        self.port.take_retries()
+    }
+}
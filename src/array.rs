@@ -0,0 +1,96 @@
+use crate::{
+    adapters::{I2CMux, MuxChannel},
+    Apds9960Drv, Apds9960I2CPort, Rgbc,
+};
+
+/// Several APDS-9960 devices behind an I²C multiplexer.
+///
+/// All devices share the fixed [`DEFAULT_ADDR`](crate::DEFAULT_ADDR), so each
+/// one is connected to its own multiplexer channel.
+pub struct Apds9960Array<A, P> {
+    mux: I2CMux<P>,
+    devices: Vec<(u8, Apds9960Drv<A>)>,
+}
+
+impl<A, P> Apds9960Array<A, P> {
+    /// Creates an array with a device on each of `channels` of `mux`.
+    ///
+    /// # Panics
+    ///
+    /// If a channel is not in `0..8`.
+    pub fn new(mux: I2CMux<P>, channels: impl IntoIterator<Item = u8>) -> Self {
+        let devices = channels
+            .into_iter()
+            .map(|channel| {
+                assert!(channel < 8, "invalid multiplexer channel {}", channel);
+                (channel, Apds9960Drv::init())
+            })
+            .collect();
+        Self { mux, devices }
+    }
+
+    /// Returns the number of devices.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.devices.len()
+    }
+
+    /// Returns `true` if the array has no devices.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty()
+    }
+
+    /// Returns the driver of `index` device together with the port for its
+    /// channel.
+    pub fn get(&mut self, index: usize) -> Option<(&mut Apds9960Drv<A>, MuxChannel<'_, P>)> {
+        let (channel, drv) = self.devices.get_mut(index)?;
+        Some((drv, self.mux.channel(*channel)))
+    }
+
+    /// Releases the multiplexer.
+    pub fn release(self) -> I2CMux<P> {
+        self.mux
+    }
+}
+
+impl<A, P: Apds9960I2CPort<A> + Send> Apds9960Array<A, P> {
+    /// Reads device IDs of all devices in turn.
+    ///
+    /// # Errors
+    ///
+    /// If the bus implementation returns `Err`, it's propagated to the caller.
+    pub async fn load_id_all(&mut self) -> Result<Vec<u8>, P::Error> {
+        let mut ids = Vec::with_capacity(self.devices.len());
+        for (channel, drv) in &mut self.devices {
+            ids.push(drv.load_id(&mut self.mux.channel(*channel)).await?);
+        }
+        Ok(ids)
+    }
+
+    /// Reads RGBC data of all devices in turn.
+    ///
+    /// # Errors
+    ///
+    /// If the bus implementation returns `Err`, it's propagated to the caller.
+    pub async fn load_rgbc_all(&mut self) -> Result<Vec<Rgbc>, P::Error> {
+        let mut data = Vec::with_capacity(self.devices.len());
+        for (channel, drv) in &mut self.devices {
+            data.push(drv.load_rgbc(&mut self.mux.channel(*channel)).await?);
+        }
+        Ok(data)
+    }
+
+    /// Reads proximity data of all devices in turn.
+    ///
+    /// # Errors
+    ///
+    /// If the bus implementation returns `Err`, it's propagated to the caller.
+    pub async fn load_pdata_all(&mut self) -> Result<Vec<u8>, P::Error> {
+        let mut data = Vec::with_capacity(self.devices.len());
+        for (channel, drv) in &mut self.devices {
+            data.push(drv.load_pdata(&mut self.mux.channel(*channel)).await?);
+        }
+        Ok(data)
+    }
+}
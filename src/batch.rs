@@ -0,0 +1,97 @@
+use crate::{reg::WritableRegister, Apds9960Drv, Apds9960I2CPort};
+
+/// A set of register values to be written with the minimum number of I²C
+/// transactions.
+///
+/// Values of registers at consecutive addresses are coalesced into a single
+/// auto-increment write. Reserved addresses are never written, so they split
+/// the batch into separate transactions.
+///
+/// ```
+/// # use smartoris_apds9960::{reg::{Atime, Enable, Pers, Wtime}, RegisterBatch};
+/// let mut batch = RegisterBatch::new();
+/// batch.push(Atime(0xDB)).push(Wtime(0xF6)).push(*Pers::default().write_apers(2));
+/// batch.push(*Enable::default().set_pon().set_aen());
+/// ```
+#[derive(Clone, Default)]
+pub struct RegisterBatch {
+    bytes: Vec<(u8, u8)>,
+}
+
+impl RegisterBatch {
+    /// Creates an empty batch.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `value` to the batch.
+    ///
+    /// If the register is already in the batch, its value is replaced.
+    pub fn push<R: WritableRegister>(&mut self, value: R) -> &mut Self {
+        let bytes = value.into_raw().to_le_bytes();
+        for (addr, &byte) in (R::ADDR..).zip(&bytes[..R::SIZE]) {
+            match self.bytes.binary_search_by_key(&addr, |&(addr, _)| addr) {
+                Ok(i) => self.bytes[i].1 = byte,
+                Err(i) => self.bytes.insert(i, (addr, byte)),
+            }
+        }
+        self
+    }
+
+    /// Returns `true` if the batch contains no registers.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Returns the number of I²C transactions needed to write the batch.
+    #[must_use]
+    pub fn transactions(&self) -> usize {
+        self.runs().count()
+    }
+
+    /// Returns the byte stored for `addr`, if any.
+    pub(crate) fn get(&self, addr: u8) -> Option<u8> {
+        self.bytes.binary_search_by_key(&addr, |&(addr, _)| addr).ok().map(|i| self.bytes[i].1)
+    }
+
+    /// Returns runs of consecutive addresses.
+    pub(crate) fn runs(&self) -> impl Iterator<Item = &[(u8, u8)]> {
+        let mut rest = self.bytes.as_slice();
+        core::iter::from_fn(move || {
+            if rest.is_empty() {
+                return None;
+            }
+            let len = rest
+                .windows(2)
+                .position(|pair| pair[0].0.wrapping_add(1) != pair[1].0)
+                .map_or(rest.len(), |i| i + 1);
+            let (run, tail) = rest.split_at(len);
+            rest = tail;
+            Some(run)
+        })
+    }
+}
+
+impl<A> Apds9960Drv<A> {
+    /// Writes all registers from `batch` using auto-increment burst writes.
+    ///
+    /// Issues one I²C transaction per run of consecutive addresses, in
+    /// ascending address order.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    /// Runs written before the failure remain applied.
+    pub async fn store_batch<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+        batch: &RegisterBatch,
+    ) -> Result<(), P::Error> {
+        for run in batch.runs() {
+            self.store_burst(i2c, run[0].0, run.iter().map(|&(_, byte)| byte)).await?;
+        }
+        Ok(())
+    }
+}
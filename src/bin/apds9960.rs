@@ -0,0 +1,336 @@
+//! APDS-9960 register inspection tool.
+
+#![warn(clippy::pedantic)]
+
+use futures::executor::block_on;
+#[cfg(target_os = "linux")]
+use smartoris_apds9960::adapters::LinuxI2C;
+use smartoris_apds9960::{
+    adapters::{Adapters, Apds9960Sim},
+    reg::RegisterInfo,
+    Apds9960Drv, Apds9960I2CPort, DEFAULT_ADDR,
+};
+use std::{convert::TryFrom, env, error::Error, process, thread, time::Duration};
+
+const USAGE: &str = "\
+Usage: apds9960 [OPTIONS] <COMMAND>
+
+Options:
+  -d, --device <PATH>     I2C character device [default: /dev/i2c-1]
+  -s, --sim               Use the in-memory simulator instead of a device
+  -a, --addr <ADDR>       Slave address [default: 0x39]
+  -i, --interval <MS>     Polling interval for `stream` [default: 100]
+  -n, --count <N>         Number of polls for `stream` [default: unlimited]
+  -f, --fifo              Drain the gesture FIFO in `dump`
+  -h, --help              Print this help
+
+Commands:
+  probe                   Check the device ID
+  dump                    Print all registers with decoded fields
+  set <REG> [VALUE]       Write VALUE to REG register, e.g. `set atime 0xdb`;
+                          VALUE is omitted for IFORCE and *CLEAR registers
+  stream <rgbc|prox|gesture>
+                          Enable the engine and print its data";
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+enum Backend {
+    Device(String),
+    Sim,
+}
+
+enum Command {
+    Probe,
+    Dump,
+    Set { reg: String, value: u16 },
+    Stream { engine: Engine },
+}
+
+#[derive(Clone, Copy)]
+enum Engine {
+    Rgbc,
+    Proximity,
+    Gesture,
+}
+
+struct Opts {
+    backend: Backend,
+    addr: u8,
+    interval: Duration,
+    count: Option<u32>,
+    gfifo: bool,
+    command: Command,
+}
+
+fn main() {
+    let opts = match parse_args(env::args().skip(1)) {
+        Ok(Some(opts)) => opts,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
+    let result = match &opts.backend {
+        #[cfg(target_os = "linux")]
+        Backend::Device(path) => LinuxI2C::open(path)
+            .map_err(Into::into)
+            .and_then(|mut i2c| block_on(run(&opts, &mut i2c))),
+        #[cfg(not(target_os = "linux"))]
+        Backend::Device(_) => Err("I2C devices are supported only on Linux".into()),
+        Backend::Sim => block_on(run(&opts, &mut sim(opts.addr))),
+    };
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Opts>> {
+    let mut backend = Backend::Device("/dev/i2c-1".into());
+    let mut addr = DEFAULT_ADDR;
+    let mut interval = Duration::from_millis(100);
+    let mut count = None;
+    let mut gfifo = false;
+    let mut positional = Vec::new();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("missing value for `{}`", arg));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-s" | "--sim" => backend = Backend::Sim,
+            "-f" | "--fifo" => gfifo = true,
+            "-d" | "--device" => backend = Backend::Device(value()?),
+            "-a" | "--addr" => addr = u8::try_from(parse_int(&value()?)?)?,
+            "-i" | "--interval" => interval = Duration::from_millis(parse_int(&value()?)?.into()),
+            "-n" | "--count" => count = Some(parse_int(&value()?)?.into()),
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg).into()),
+            _ => positional.push(arg),
+        }
+    }
+    let command = match positional.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["probe"] => Command::Probe,
+        ["dump"] => Command::Dump,
+        ["set", reg, value] => {
+            Command::Set { reg: reg.to_ascii_lowercase(), value: parse_int(value)? }
+        }
+        ["set", reg] => match RegisterInfo::by_name(reg) {
+            Some(info) if info.size() > 0 => {
+                return Err(format!("missing value for {} register", info.name()).into());
+            }
+            _ => Command::Set { reg: reg.to_ascii_lowercase(), value: 0 },
+        },
+        ["stream", engine] => Command::Stream {
+            engine: match *engine {
+                "rgbc" => Engine::Rgbc,
+                "prox" => Engine::Proximity,
+                "gesture" => Engine::Gesture,
+                _ => return Err(format!("unknown engine `{}`", engine).into()),
+            },
+        },
+        [] => return Err("missing command".into()),
+        _ => return Err(format!("invalid command `{}`", positional.join(" ")).into()),
+    };
+    Ok(Some(Opts { backend, addr, interval, count, gfifo, command }))
+}
+
+fn parse_int(value: &str) -> Result<u16> {
+    Ok(match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u16::from_str_radix(hex, 16)?,
+        None => value.parse()?,
+    })
+}
+
+fn sim(addr: u8) -> Apds9960Sim {
+    let mut sim = Apds9960Sim::new();
+    sim.set_addr(addr);
+    sim.set_rgbc(1200, 400, 500, 300);
+    sim.set_pdata(42);
+    for dataset in &[[10, 12, 40, 8], [20, 22, 30, 18], [40, 38, 12, 36]] {
+        sim.push_gesture(*dataset);
+    }
+    sim
+}
+
+async fn run<P>(opts: &Opts, i2c: &mut P) -> Result<()>
+where
+    P: Apds9960I2CPort<Adapters>,
+    P::Error: Error + 'static,
+{
+    let mut drv = Apds9960Drv::init();
+    drv.set_addr(opts.addr);
+    match &opts.command {
+        Command::Probe => probe(&mut drv, i2c).await,
+        Command::Dump => dump(&mut drv, i2c, opts.gfifo).await,
+        Command::Set { reg, value } => set(&mut drv, i2c, reg, *value).await,
+        Command::Stream { engine } => {
+            stream(&mut drv, i2c, *engine, opts.interval, opts.count).await
+        }
+    }
+}
+
+async fn probe<P>(drv: &mut Apds9960Drv<Adapters>, i2c: &mut P) -> Result<()>
+where
+    P: Apds9960I2CPort<Adapters>,
+    P::Error: Error + 'static,
+{
+    let id = drv.load_id(i2c).await?;
+    if id == 0xAB {
+        println!("APDS-9960 found (ID {:#04x})", id);
+        Ok(())
+    } else {
+        Err(format!("unexpected device ID {:#04x}", id).into())
+    }
+}
+
+async fn dump<P>(drv: &mut Apds9960Drv<Adapters>, i2c: &mut P, gfifo: bool) -> Result<()>
+where
+    P: Apds9960I2CPort<Adapters>,
+    P::Error: Error + 'static,
+{
+    println!("{}", drv.dump_registers(i2c, gfifo).await?);
+    Ok(())
+}
+
+async fn set<P>(drv: &mut Apds9960Drv<Adapters>, i2c: &mut P, reg: &str, value: u16) -> Result<()>
+where
+    P: Apds9960I2CPort<Adapters>,
+    P::Error: Error + 'static,
+{
+    let info = RegisterInfo::by_name(reg).ok_or_else(|| format!("unknown register `{}`", reg))?;
+    if !info.is_writable() {
+        return Err(format!("{} register is read-only", info.name()).into());
+    }
+    if !info.validate(value) {
+        return Err(format!("{:#x} is not a valid {} value", value, info.name()).into());
+    }
+    macro_rules! set {
+        ($($name:literal => $store:ident $kind:ident,)*) => {
+            match reg {
+                $($name => set!(@$kind $store),)*
+                _ => unreachable!(),
+            }
+        };
+        (@u8 $store:ident) => {
+            drv.$store(i2c, u8::try_from(value)?.into()).await?
+        };
+        (@u16 $store:ident) => {
+            drv.$store(i2c, value).await?
+        };
+        (@touch $touch:ident) => {
+            drv.$touch(i2c).await?
+        };
+    }
+    set! {
+        "enable" => store_enable_val u8,
+        "atime" => store_atime u8,
+        "wtime" => store_wtime u8,
+        "ailt" => store_ailt u16,
+        "aiht" => store_aiht u16,
+        "pilt" => store_pilt u8,
+        "piht" => store_piht u8,
+        "pers" => store_pers_val u8,
+        "config1" => store_config1_val u8,
+        "ppulse" => store_ppulse_val u8,
+        "control" => store_control_val u8,
+        "config2" => store_config2_val u8,
+        "poffset_ur" => store_poffset_ur u8,
+        "poffset_dl" => store_poffset_dl u8,
+        "config3" => store_config3_val u8,
+        "gpenth" => store_gpenth u8,
+        "gexth" => store_gexth u8,
+        "gconf1" => store_gconf1_val u8,
+        "gconf2" => store_gconf2_val u8,
+        "goffset_u" => store_goffset_u u8,
+        "goffset_d" => store_goffset_d u8,
+        "gpulse" => store_gpulse_val u8,
+        "goffset_l" => store_goffset_l u8,
+        "goffset_r" => store_goffset_r u8,
+        "gconf3" => store_gconf3_val u8,
+        "gconf4" => store_gconf4_val u8,
+        "iforce" => touch_iforce touch,
+        "piclear" => touch_piclear touch,
+        "ciclear" => touch_ciclear touch,
+        "aiclear" => touch_aiclear touch,
+    }
+    Ok(())
+}
+
+async fn stream<P>(
+    drv: &mut Apds9960Drv<Adapters>,
+    i2c: &mut P,
+    engine: Engine,
+    interval: Duration,
+    count: Option<u32>,
+) -> Result<()>
+where
+    P: Apds9960I2CPort<Adapters>,
+    P::Error: Error + 'static,
+{
+    match engine {
+        Engine::Rgbc => drv.store_enable(i2c, |r| r.set_pon().set_aen()).await?,
+        Engine::Proximity => drv.store_enable(i2c, |r| r.set_pon().set_pen()).await?,
+        Engine::Gesture => {
+            drv.store_gconf4(i2c, |r| r.set_gmode()).await?;
+            drv.store_enable(i2c, |r| r.set_pon().set_pen().set_gen()).await?;
+        }
+    }
+    let mut polls = 0;
+    while count.map_or(true, |count| polls < count) {
+        let status = drv.load_status(i2c).await?;
+        match engine {
+            Engine::Rgbc if status.avalid() => {
+                let c = drv.load_cdata(i2c).await?;
+                let r = drv.load_rdata(i2c).await?;
+                let g = drv.load_gdata(i2c).await?;
+                let b = drv.load_bdata(i2c).await?;
+                println!("c={} r={} g={} b={}", c, r, g, b);
+            }
+            Engine::Proximity if status.pvalid() => {
+                println!("p={}", drv.load_pdata(i2c).await?);
+            }
+            Engine::Gesture if status.gint() => {
+                let level = drv.load_gflvl(i2c).await?;
+                for dataset in drv.drain_fifo(i2c, level).await?.chunks(4) {
+                    println!("u={} d={} l={} r={}", dataset[0], dataset[1], dataset[2], dataset[3]);
+                }
+            }
+            _ => {}
+        }
+        polls += 1;
+        thread::sleep(interval);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Opts>> {
+        parse_args(args.iter().map(|&arg| arg.to_string()))
+    }
+
+    #[test]
+    fn set_requires_value() {
+        assert!(parse(&["set", "enable"]).is_err());
+        assert!(parse(&["set", "ATIME"]).is_err());
+        assert!(parse(&["set", "ailt"]).is_err());
+    }
+
+    #[test]
+    fn set_touch_without_value() {
+        for reg in &["iforce", "piclear", "ciclear", "AICLEAR"] {
+            let opts = parse(&["--sim", "set", reg]).unwrap().unwrap();
+            assert!(matches!(opts.command, Command::Set { value: 0, .. }));
+        }
+    }
+
+    #[test]
+    fn set_with_value() {
+        let opts = parse(&["set", "atime", "0xdb"]).unwrap().unwrap();
+        assert!(matches!(opts.command, Command::Set { ref reg, value: 0xDB } if reg == "atime"));
+    }
+}
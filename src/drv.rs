@@ -1,15 +1,22 @@
-use crate::{Apds9960I2CPort, DEFAULT_ADDR};
+use crate::{
+    reg::{ReadableRegister, WritableRegister},
+    Apds9960I2CPort, BusStats, DEFAULT_ADDR,
+};
 use core::{marker::PhantomData, mem::take};
 
 /// Internal buffer size.
-const BUF_SIZE: usize = 32 * 4;
+const BUF_SIZE: usize = GFIFO_DEPTH as usize * 4;
 
 const GFIFO: u8 = 0xFC;
 
+/// Gesture FIFO depth in datasets.
+const GFIFO_DEPTH: u8 = 32;
+
 /// APDS-9960 driver.
 pub struct Apds9960Drv<A> {
     pub(crate) addr: u8,
     pub(crate) buf: Box<[u8]>,
+    pub(crate) stats: Option<BusStats>,
     adapters: PhantomData<A>,
 }
 
@@ -22,6 +29,7 @@ impl<A> Apds9960Drv<A> {
         Self {
             addr: DEFAULT_ADDR,
             buf: vec![0; BUF_SIZE].into_boxed_slice(),
+            stats: None,
             adapters: PhantomData,
         }
     }
@@ -31,8 +39,55 @@ impl<A> Apds9960Drv<A> {
         self.addr = addr;
     }
 
+    /// Reads contents of `R` register.
+    ///
+    /// The port type can be left for inference: `load::<Enable, _>(i2c)`.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn load<R: ReadableRegister, P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+    ) -> Result<R, P::Error> {
+        self.load_reg(i2c, R::ADDR, R::SIZE).await.map(R::from_raw)
+    }
+
+    /// Writes `value` to `R` register.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn store<R: WritableRegister, P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+        value: R,
+    ) -> Result<(), P::Error> {
+        self.store_reg(i2c, value.into_raw(), R::ADDR, R::SIZE).await
+    }
+
+    /// Reads contents of `R` register, passes it to the closure `f`, then
+    /// writes the result of the closure back to the register.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn modify<R, P, F>(&mut self, i2c: &mut P, f: F) -> Result<(), P::Error>
+    where
+        R: ReadableRegister + WritableRegister,
+        P: Apds9960I2CPort<A>,
+        F: FnOnce(&mut R) -> &mut R,
+    {
+        let mut value = self.load::<R, P>(i2c).await?;
+        f(&mut value);
+        self.store(i2c, value).await
+    }
+
     /// Performs a page read of `level` number of gesture datasets from FIFO.
     ///
+    /// `level` is clamped to the FIFO depth of 32 datasets, so a corrupted
+    /// GFLVL value doesn't overrun the internal buffer.
+    ///
     /// # Errors
     ///
     /// If `i2c` implementation returns `Err`, it's propagated to the caller.
@@ -40,17 +95,27 @@ impl<A> Apds9960Drv<A> {
         &mut self,
         i2c: &mut P,
         level: u8,
+    ) -> Result<&[u8], P::Error> {
+        self.load_burst(i2c, GFIFO, usize::from(level.min(GFIFO_DEPTH)) * 4).await
+    }
+
+    pub(crate) async fn load_burst<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+        reg: u8,
+        size: usize,
     ) -> Result<&[u8], P::Error> {
         let mut buf = take(&mut self.buf);
-        buf[0] = GFIFO;
-        let size = (level << 2) as usize;
+        buf[0] = reg;
         match i2c.read(self.addr, buf, size).await {
             Ok(buf) => {
                 self.buf = buf;
+                self.record_load(reg, size, true, i2c.take_retries());
                 Ok(&self.buf[0..size])
             }
             Err((buf, err)) => {
                 self.buf = buf;
+                self.record_load(reg, size, false, i2c.take_retries());
                 Err(err)
             }
         }
@@ -69,10 +134,39 @@ impl<A> Apds9960Drv<A> {
         match i2c.write(self.addr, buf, size + 1).await {
             Ok(buf) => {
                 self.buf = buf;
+                self.record_store(reg, size + 1, true, i2c.take_retries());
+                Ok(())
+            }
+            Err((buf, err)) => {
+                self.buf = buf;
+                self.record_store(reg, size + 1, false, i2c.take_retries());
+                Err(err)
+            }
+        }
+    }
+
+    pub(crate) async fn store_burst<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+        reg: u8,
+        bytes: impl IntoIterator<Item = u8>,
+    ) -> Result<(), P::Error> {
+        let mut buf = take(&mut self.buf);
+        buf[0] = reg;
+        let mut count = 1;
+        for byte in bytes {
+            buf[count] = byte;
+            count += 1;
+        }
+        match i2c.write(self.addr, buf, count).await {
+            Ok(buf) => {
+                self.buf = buf;
+                self.record_store(reg, count, true, i2c.take_retries());
                 Ok(())
             }
             Err((buf, err)) => {
                 self.buf = buf;
+                self.record_store(reg, count, false, i2c.take_retries());
                 Err(err)
             }
         }
@@ -89,12 +183,14 @@ impl<A> Apds9960Drv<A> {
         match i2c.read(self.addr, buf, size).await {
             Ok(buf) => {
                 self.buf = buf;
+                self.record_load(reg, size, true, i2c.take_retries());
                 let mut value = 0_u16.to_le_bytes();
                 value[..size].copy_from_slice(&self.buf[..size]);
                 Ok(u16::from_le_bytes(value))
             }
             Err((buf, err)) => {
                 self.buf = buf;
+                self.record_load(reg, size, false, i2c.take_retries());
                 Err(err)
             }
         }
@@ -110,10 +206,12 @@ impl<A> Apds9960Drv<A> {
         match i2c.write(self.addr, buf, 1).await {
             Ok(buf) => {
                 self.buf = buf;
+                self.record_store(reg, 1, true, i2c.take_retries());
                 Ok(())
             }
             Err((buf, err)) => {
                 self.buf = buf;
+                self.record_store(reg, 1, false, i2c.take_retries());
                 Err(err)
             }
         }
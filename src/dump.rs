@@ -0,0 +1,420 @@
+use crate::{
+    reg::{
+        Config1, Config2, Config3, Control, Enable, Gconf1, Gconf2, Gconf3, Gconf4, Gpulse,
+        Gstatus, Pers, Ppulse, Status,
+    },
+    Apds9960Drv, Apds9960I2CPort,
+};
+use core::fmt;
+
+/// The first register address of a dump.
+const DUMP_START: u8 = 0x80;
+
+/// Number of bytes in a dump.
+const DUMP_SIZE: usize = 0x30;
+
+/// GFLVL register address.
+const GFLVL: u8 = 0xAE;
+
+/// A snapshot of all APDS-9960 registers.
+///
+/// Obtained with [`Apds9960Drv::dump_registers`]. The [`fmt::Display`]
+/// implementation prints every register by name with decoded bitfields.
+#[derive(Clone)]
+pub struct RegisterDump {
+    regs: [u8; DUMP_SIZE],
+    gfifo: Vec<[u8; 4]>,
+}
+
+macro_rules! dump_reg {
+    ($(#[$attr:meta] $name:ident $type:ident $size:tt $addr:literal;)*) => {
+        impl RegisterDump {
+            $(
+                #[$attr]
+                #[must_use]
+                pub fn $name(&self) -> $type {
+                    dump_reg!(@ self $type $size $addr)
+                }
+            )*
+        }
+    };
+    (@ $self:ident $type:ident 1 $addr:literal) => {
+        $type::from($self.byte($addr))
+    };
+    (@ $self:ident $type:ident 2 $addr:literal) => {
+        u16::from_le_bytes([$self.byte($addr), $self.byte($addr + 1)])
+    };
+}
+
+dump_reg! {
+    /// Returns the value of ENABLE register.
+    enable Enable 1 0x80;
+    /// Returns the value of ATIME register.
+    atime u8 1 0x81;
+    /// Returns the value of WTIME register.
+    wtime u8 1 0x83;
+    /// Returns the value of AILT register.
+    ailt u16 2 0x84;
+    /// Returns the value of AIHT register.
+    aiht u16 2 0x86;
+    /// Returns the value of PILT register.
+    pilt u8 1 0x89;
+    /// Returns the value of PIHT register.
+    piht u8 1 0x8B;
+    /// Returns the value of PERS register.
+    pers Pers 1 0x8C;
+    /// Returns the value of CONFIG1 register.
+    config1 Config1 1 0x8D;
+    /// Returns the value of PPULSE register.
+    ppulse Ppulse 1 0x8E;
+    /// Returns the value of CONTROL register.
+    control Control 1 0x8F;
+    /// Returns the value of CONFIG2 register.
+    config2 Config2 1 0x90;
+    /// Returns the value of ID register.
+    id u8 1 0x92;
+    /// Returns the value of STATUS register.
+    status Status 1 0x93;
+    /// Returns the value of CDATA register.
+    cdata u16 2 0x94;
+    /// Returns the value of RDATA register.
+    rdata u16 2 0x96;
+    /// Returns the value of GDATA register.
+    gdata u16 2 0x98;
+    /// Returns the value of BDATA register.
+    bdata u16 2 0x9A;
+    /// Returns the value of PDATA register.
+    pdata u8 1 0x9C;
+    /// Returns the value of POFFSET_UR register.
+    poffset_ur u8 1 0x9D;
+    /// Returns the value of POFFSET_DL register.
+    poffset_dl u8 1 0x9E;
+    /// Returns the value of CONFIG3 register.
+    config3 Config3 1 0x9F;
+    /// Returns the value of GPENTH register.
+    gpenth u8 1 0xA0;
+    /// Returns the value of GEXTH register.
+    gexth u8 1 0xA1;
+    /// Returns the value of GCONF1 register.
+    gconf1 Gconf1 1 0xA2;
+    /// Returns the value of GCONF2 register.
+    gconf2 Gconf2 1 0xA3;
+    /// Returns the value of GOFFSET_U register.
+    goffset_u u8 1 0xA4;
+    /// Returns the value of GOFFSET_D register.
+    goffset_d u8 1 0xA5;
+    /// Returns the value of GPULSE register.
+    gpulse Gpulse 1 0xA6;
+    /// Returns the value of GOFFSET_L register.
+    goffset_l u8 1 0xA7;
+    /// Returns the value of GOFFSET_R register.
+    goffset_r u8 1 0xA9;
+    /// Returns the value of GCONF3 register.
+    gconf3 Gconf3 1 0xAA;
+    /// Returns the value of GCONF4 register.
+    gconf4 Gconf4 1 0xAB;
+    /// Returns the value of GFLVL register.
+    gflvl u8 1 0xAE;
+    /// Returns the value of GSTATUS register.
+    gstatus Gstatus 1 0xAF;
+}
+
+impl RegisterDump {
+    /// Returns the raw value of `addr` register, or `None` if the address is
+    /// outside of the dumped `0x80..=0xAF` range.
+    #[must_use]
+    pub fn raw(&self, addr: u8) -> Option<u8> {
+        addr.checked_sub(DUMP_START).and_then(|offset| self.regs.get(offset as usize)).copied()
+    }
+
+    /// Returns the gesture datasets read from FIFO.
+    ///
+    /// Empty unless the dump was requested with the gesture FIFO.
+    #[must_use]
+    pub fn gfifo(&self) -> &[[u8; 4]] {
+        &self.gfifo
+    }
+
+    fn byte(&self, addr: u8) -> u8 {
+        self.regs[(addr - DUMP_START) as usize]
+    }
+}
+
+impl<A> Apds9960Drv<A> {
+    /// Reads all registers from `0x80` to `0xAF` in a single burst.
+    ///
+    /// If `gfifo` is `true`, also drains all gesture datasets currently in
+    /// FIFO.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn dump_registers<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+        gfifo: bool,
+    ) -> Result<RegisterDump, P::Error> {
+        let mut regs = [0; DUMP_SIZE];
+        regs.copy_from_slice(self.load_burst(i2c, DUMP_START, DUMP_SIZE).await?);
+        let gfifo = if gfifo {
+            let level = regs[(GFLVL - DUMP_START) as usize];
+            self.drain_fifo(i2c, level)
+                .await?
+                .chunks_exact(4)
+                .map(|dataset| [dataset[0], dataset[1], dataset[2], dataset[3]])
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Ok(RegisterDump { regs, gfifo })
+    }
+}
+
+const GAIN_ALS: [&str; 4] = ["1x", "4x", "16x", "64x"];
+const GAIN_PROXIMITY: [&str; 4] = ["1x", "2x", "4x", "8x"];
+const LED_DRIVE: [&str; 4] = ["100mA", "50mA", "25mA", "12.5mA"];
+const LED_BOOST: [&str; 4] = ["100%", "150%", "200%", "300%"];
+const PULSE_LENGTH: [&str; 4] = ["4us", "8us", "16us", "32us"];
+const GESTURE_WAIT: [&str; 8] =
+    ["0ms", "2.8ms", "5.6ms", "8.4ms", "14.0ms", "22.4ms", "30.8ms", "39.2ms"];
+const GESTURE_FIFO_THRESHOLD: [u8; 4] = [1, 4, 8, 16];
+const GESTURE_EXIT_PERSISTENCE: [u8; 4] = [1, 2, 4, 7];
+const GESTURE_DIMENSIONS: [&str; 4] = ["all", "up-down", "left-right", "all"];
+
+/// Decodes a sign-magnitude offset register.
+fn offset(value: u8) -> i16 {
+    let magnitude = i16::from(value & 0x7F);
+    if value & 0x80 == 0 { magnitude } else { -magnitude }
+}
+
+/// Converts ATIME or WTIME `value` to a duration.
+fn cycles(value: u8, scale: u32) -> Millis {
+    Millis((256 - u32::from(value)) * 278 * scale)
+}
+
+/// Hundredths of a millisecond.
+struct Millis(u32);
+
+impl fmt::Display for Millis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{:02}ms", self.0 / 100, self.0 % 100)
+    }
+}
+
+impl fmt::Display for RegisterDump {
+    #[allow(clippy::too_many_lines)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bit = u8::from;
+        let line = |f: &mut fmt::Formatter<'_>, name: &str, addr: u8| {
+            write!(f, "{:<10} [0x{:02X}] ", name, addr)
+        };
+
+        let r = self.enable();
+        line(f, "ENABLE", 0x80)?;
+        writeln!(
+            f,
+            "{:#04x}: pon={} aen={} pen={} wen={} aien={} pien={} gen={}",
+            u8::from(r),
+            bit(r.pon()),
+            bit(r.aen()),
+            bit(r.pen()),
+            bit(r.wen()),
+            bit(r.aien()),
+            bit(r.pien()),
+            bit(r.gen())
+        )?;
+
+        line(f, "ATIME", 0x81)?;
+        writeln!(f, "{:#04x}: integration={}", self.atime(), cycles(self.atime(), 1))?;
+
+        let wlong = if self.config1().wlong() { 12 } else { 1 };
+        line(f, "WTIME", 0x83)?;
+        writeln!(f, "{:#04x}: wait={}", self.wtime(), cycles(self.wtime(), wlong))?;
+
+        line(f, "AILT", 0x84)?;
+        writeln!(f, "{:#06x}: {}", self.ailt(), self.ailt())?;
+        line(f, "AIHT", 0x86)?;
+        writeln!(f, "{:#06x}: {}", self.aiht(), self.aiht())?;
+        line(f, "PILT", 0x89)?;
+        writeln!(f, "{:#04x}: {}", self.pilt(), self.pilt())?;
+        line(f, "PIHT", 0x8B)?;
+        writeln!(f, "{:#04x}: {}", self.piht(), self.piht())?;
+
+        let r = self.pers();
+        line(f, "PERS", 0x8C)?;
+        writeln!(
+            f,
+            "{:#04x}: apers={} ppers={}",
+            u8::from(r),
+            r.als_persistence().cycles(),
+            r.proximity_persistence().cycles()
+        )?;
+
+        let r = self.config1();
+        line(f, "CONFIG1", 0x8D)?;
+        writeln!(f, "{:#04x}: wlong={}", u8::from(r), bit(r.wlong()))?;
+
+        let r = self.ppulse();
+        line(f, "PPULSE", 0x8E)?;
+        writeln!(
+            f,
+            "{:#04x}: ppulse={} pplen={}",
+            u8::from(r),
+            r.ppulse() + 1,
+            PULSE_LENGTH[r.pplen() as usize]
+        )?;
+
+        let r = self.control();
+        line(f, "CONTROL", 0x8F)?;
+        writeln!(
+            f,
+            "{:#04x}: again={} pgain={} ldrive={}",
+            u8::from(r),
+            GAIN_ALS[r.again() as usize],
+            GAIN_PROXIMITY[r.pgain() as usize],
+            LED_DRIVE[r.ldrive() as usize]
+        )?;
+
+        let r = self.config2();
+        line(f, "CONFIG2", 0x90)?;
+        writeln!(
+            f,
+            "{:#04x}: led_boost={} cpsien={} psien={}",
+            u8::from(r),
+            LED_BOOST[r.led_boost() as usize],
+            bit(r.cpsien()),
+            bit(r.psien())
+        )?;
+
+        line(f, "ID", 0x92)?;
+        writeln!(f, "{:#04x}", self.id())?;
+
+        let r = self.status();
+        line(f, "STATUS", 0x93)?;
+        writeln!(
+            f,
+            "{:#04x}: avalid={} pvalid={} gint={} aint={} pint={} pgsat={} cpsat={}",
+            u8::from(r),
+            bit(r.avalid()),
+            bit(r.pvalid()),
+            bit(r.gint()),
+            bit(r.aint()),
+            bit(r.pint()),
+            bit(r.pgsat()),
+            bit(r.cpsat())
+        )?;
+
+        line(f, "CDATA", 0x94)?;
+        writeln!(f, "{:#06x}: {}", self.cdata(), self.cdata())?;
+        line(f, "RDATA", 0x96)?;
+        writeln!(f, "{:#06x}: {}", self.rdata(), self.rdata())?;
+        line(f, "GDATA", 0x98)?;
+        writeln!(f, "{:#06x}: {}", self.gdata(), self.gdata())?;
+        line(f, "BDATA", 0x9A)?;
+        writeln!(f, "{:#06x}: {}", self.bdata(), self.bdata())?;
+        line(f, "PDATA", 0x9C)?;
+        writeln!(f, "{:#04x}: {}", self.pdata(), self.pdata())?;
+        line(f, "POFFSET_UR", 0x9D)?;
+        writeln!(f, "{:#04x}: {}", self.poffset_ur(), offset(self.poffset_ur()))?;
+        line(f, "POFFSET_DL", 0x9E)?;
+        writeln!(f, "{:#04x}: {}", self.poffset_dl(), offset(self.poffset_dl()))?;
+
+        let r = self.config3();
+        line(f, "CONFIG3", 0x9F)?;
+        writeln!(
+            f,
+            "{:#04x}: pmask_r={} pmask_l={} pmask_d={} pmask_u={} sai={} pcmp={}",
+            u8::from(r),
+            bit(r.pmask_r()),
+            bit(r.pmask_l()),
+            bit(r.pmask_d()),
+            bit(r.pmask_u()),
+            bit(r.sai()),
+            bit(r.pcmp())
+        )?;
+
+        line(f, "GPENTH", 0xA0)?;
+        writeln!(f, "{:#04x}: {}", self.gpenth(), self.gpenth())?;
+        line(f, "GEXTH", 0xA1)?;
+        writeln!(f, "{:#04x}: {}", self.gexth(), self.gexth())?;
+
+        let r = self.gconf1();
+        line(f, "GCONF1", 0xA2)?;
+        writeln!(
+            f,
+            "{:#04x}: gexpers={} gexmsk={:#06b} gfifoth={}",
+            u8::from(r),
+            GESTURE_EXIT_PERSISTENCE[r.gexpers() as usize],
+            r.gexmsk(),
+            GESTURE_FIFO_THRESHOLD[r.gfifoth() as usize]
+        )?;
+
+        let r = self.gconf2();
+        line(f, "GCONF2", 0xA3)?;
+        writeln!(
+            f,
+            "{:#04x}: gwtime={} gldrive={} ggain={}",
+            u8::from(r),
+            GESTURE_WAIT[r.gwtime() as usize],
+            LED_DRIVE[r.gldrive() as usize],
+            GAIN_PROXIMITY[r.ggain() as usize]
+        )?;
+
+        line(f, "GOFFSET_U", 0xA4)?;
+        writeln!(f, "{:#04x}: {}", self.goffset_u(), offset(self.goffset_u()))?;
+        line(f, "GOFFSET_D", 0xA5)?;
+        writeln!(f, "{:#04x}: {}", self.goffset_d(), offset(self.goffset_d()))?;
+
+        let r = self.gpulse();
+        line(f, "GPULSE", 0xA6)?;
+        writeln!(
+            f,
+            "{:#04x}: gpulse={} gplen={}",
+            u8::from(r),
+            r.gpulse() + 1,
+            PULSE_LENGTH[r.gplen() as usize]
+        )?;
+
+        line(f, "GOFFSET_L", 0xA7)?;
+        writeln!(f, "{:#04x}: {}", self.goffset_l(), offset(self.goffset_l()))?;
+        line(f, "GOFFSET_R", 0xA9)?;
+        writeln!(f, "{:#04x}: {}", self.goffset_r(), offset(self.goffset_r()))?;
+
+        let r = self.gconf3();
+        line(f, "GCONF3", 0xAA)?;
+        writeln!(f, "{:#04x}: gdims={}", u8::from(r), GESTURE_DIMENSIONS[r.gdims() as usize])?;
+
+        let r = self.gconf4();
+        line(f, "GCONF4", 0xAB)?;
+        writeln!(
+            f,
+            "{:#04x}: gmode={} gien={} gfifo_clr={}",
+            u8::from(r),
+            bit(r.gmode()),
+            bit(r.gien()),
+            bit(r.gfifo_clr())
+        )?;
+
+        line(f, "GFLVL", 0xAE)?;
+        writeln!(f, "{:#04x}: {}", self.gflvl(), self.gflvl())?;
+
+        let r = self.gstatus();
+        line(f, "GSTATUS", 0xAF)?;
+        write!(f, "{:#04x}: gvalid={} gfov={}", u8::from(r), bit(r.gvalid()), bit(r.gfov()))?;
+
+        for (i, dataset) in self.gfifo.iter().enumerate() {
+            write!(
+                f,
+                "\nGFIFO[{:02}]  u={} d={} l={} r={}",
+                i, dataset[0], dataset[1], dataset[2], dataset[3]
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for RegisterDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
@@ -0,0 +1,63 @@
+use core::fmt;
+
+/// APDS-9960 driver error.
+///
+/// Returned by higher-level operations that can fail for reasons other than
+/// the I²C port.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Apds9960Error<E> {
+    /// The I²C port returned an error.
+    Port(E),
+    /// The low threshold of an interrupt window is above the high threshold.
+    InvalidWindow {
+        /// Requested low threshold.
+        low: u16,
+        /// Requested high threshold.
+        high: u16,
+    },
+    /// The device didn't reach the expected state in time.
+    Timeout,
+    /// The device is powered off or has no engine enabled, so it can't reach
+    /// the expected state.
+    Idle,
+    /// A register read back after a write differs from the written value.
+    VerifyFailed {
+        /// Register address.
+        reg: u8,
+        /// Written value.
+        wrote: u8,
+        /// Read back value.
+        read: u8,
+    },
+}
+
+impl<E: fmt::Display> fmt::Display for Apds9960Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Port(err) => write!(f, "I2C port error: {}", err),
+            Self::InvalidWindow { low, high } => {
+                write!(f, "invalid threshold window: low {} is above high {}", low, high)
+            }
+            Self::Timeout => write!(f, "timed out waiting for the device"),
+            Self::Idle => write!(f, "no measurement engine is running"),
+            Self::VerifyFailed { reg, wrote, read } => write!(
+                f,
+                "register 0x{:02X} verification failed: wrote 0x{:02X}, read 0x{:02X}",
+                reg, wrote, read
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error + 'static> std::error::Error for Apds9960Error<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Port(err) => Some(err),
+            Self::InvalidWindow { .. } | Self::Timeout | Self::Idle | Self::VerifyFailed { .. } => {
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{Apds9960DelayPort, Apds9960Drv, Apds9960I2CPort, Apds9960IntPort};
+use core::time::Duration;
+use futures::future::{self, Either};
+
+/// Time allowed for the INT line to rise after the interrupt is cleared.
+const RELEASE_DELAY: Duration = Duration::from_millis(1);
+
+/// Result of [`Apds9960Drv::test_int_wiring`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct IntWiringReport {
+    /// The INT line was high with no interrupt pending, so the pull-up works.
+    pub idle_high: bool,
+    /// The INT line went low after a forced interrupt within the timeout.
+    pub asserted: bool,
+    /// The INT line went high again after the interrupt was cleared.
+    pub released: bool,
+}
+
+impl IntWiringReport {
+    /// Returns `true` if all checks passed.
+    #[must_use]
+    pub fn passed(self) -> bool {
+        self.idle_high && self.asserted && self.released
+    }
+}
+
+impl<A> Apds9960Drv<A> {
+    /// Checks the INT line wiring and pull-up.
+    ///
+    /// Clears pending interrupts with AICLEAR and checks that the line is
+    /// high, forces an interrupt with IFORCE and waits up to `timeout` for the
+    /// line to go low, then clears the interrupt with AICLEAR and checks that
+    /// the line is released. `Enable::pon` and `Enable::aien` are set for the
+    /// duration of the test, and ENABLE register is restored afterwards.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn test_int_wiring<P, D, I>(
+        &mut self,
+        i2c: &mut P,
+        delay: &mut D,
+        int: &mut I,
+        timeout: Duration,
+    ) -> Result<IntWiringReport, P::Error>
+    where
+        P: Apds9960I2CPort<A>,
+        D: Apds9960DelayPort<A>,
+        I: Apds9960IntPort<A>,
+    {
+        let enable = self.load_enable(i2c).await?;
+        let mut value = enable;
+        value.set_pon().set_aien();
+        self.store_enable_val(i2c, value).await?;
+        self.touch_aiclear(i2c).await?;
+        delay.delay(RELEASE_DELAY).await;
+        let idle_high = !int.is_low();
+        self.touch_iforce(i2c).await?;
+        let waited = matches!(
+            future::select(int.wait_low(), delay.delay(timeout)).await,
+            Either::Left(((), _))
+        );
+        let asserted = waited || int.is_low();
+        self.touch_aiclear(i2c).await?;
+        delay.delay(RELEASE_DELAY).await;
+        let released = !int.is_low();
+        self.store_enable_val(i2c, enable).await?;
+        Ok(IntWiringReport { idle_high, asserted, released })
+    }
+}
@@ -17,9 +17,19 @@
 //! std = ["smartoris-apds9960/std"]
 //! ```
 //!
-//! The driver can be used with any I²C implementation. Here is an example of
-//! integration with [`smartoris-i2c`](https://crates.io/crates/smartoris-i2c)
-//! crate.
+//! Register types implement `defmt::Format` when `defmt` feature is enabled.
+//! [`SelfTestReport`] and [`Rgbc`] implement `serde` traits when `serde` feature
+//! is enabled.
+//!
+//! The driver can be used with any I²C implementation through
+//! [`Apds9960I2CPort`] trait. An adapter for
+//! [`smartoris-i2c`](https://crates.io/crates/smartoris-i2c) crate is
+//! included and can be enabled with `smartoris-i2c` feature:
+//!
+//! ```toml
+//! [dependencies]
+//! smartoris-apds9960 = { version = "0.1.0", features = ["smartoris-i2c"] }
+//! ```
 //!
 //! ```no_run
 //! # #![feature(const_fn_fn_ptr_basics)]
@@ -48,7 +58,7 @@
 //! #     }
 //! # }
 //! # async fn handler() {
-//! # let mut i2c1: smartoris_i2c::I2CDrv<
+//! # let mut i2c1: smartoris_i2c_dep::I2CDrv<
 //! #     I2C1,
 //! #     thr::I2C1Ev,
 //! #     thr::I2C1Er,
@@ -57,58 +67,9 @@
 //! #     Dma1Ch5,
 //! #     thr::Dma1Ch5,
 //! # > = unsafe { core::mem::MaybeUninit::uninit().assume_init() };
-//! /// Adapters for external crates.
-//! mod adapters {
-//!     /// A marker type for port implementations in this module.
-//!     pub struct Adapters;
-//!
-//!     /// APDS-9960 adapters.
-//!     mod apds9960 {
-//!         use super::Adapters;
-//!         use async_trait::async_trait;
-//!         use drone_cortexm::thr::prelude::*;
-//!         use drone_stm32_map::periph::{dma::ch::DmaChMap, i2c::I2CMap};
-//!         use smartoris_apds9960::Apds9960I2CPort;
-//!         use smartoris_i2c::I2CDrv;
-//!
-//!         #[async_trait]
-//!         impl<
-//!             I2C: I2CMap,
-//!             I2CEv: IntToken,
-//!             I2CEr: IntToken,
-//!             DmaTx: DmaChMap,
-//!             DmaTxInt: IntToken,
-//!             DmaRx: DmaChMap,
-//!             DmaRxInt: IntToken,
-//!         > Apds9960I2CPort<Adapters>
-//!             for I2CDrv<I2C, I2CEv, I2CEr, DmaTx, DmaTxInt, DmaRx, DmaRxInt>
-//!         {
-//!             type Error = !;
-//!
-//!             async fn write(
-//!                 &mut self,
-//!                 addr: u8,
-//!                 buf: Box<[u8]>,
-//!                 count: usize,
-//!             ) -> Result<Box<[u8]>, (Box<[u8]>, !)> {
-//!                 Ok(self.master(buf).write(addr, ..count).await.stop())
-//!             }
-//!
-//!             async fn read(
-//!                 &mut self,
-//!                 addr: u8,
-//!                 buf: Box<[u8]>,
-//!                 count: usize,
-//!             ) -> Result<Box<[u8]>, (Box<[u8]>, !)> {
-//!                 Ok(self.master(buf).write(addr, ..1).await.read(addr, ..count).await.stop())
-//!             }
-//!         }
-//!     }
-//! }
+//! use smartoris_apds9960::{adapters::Adapters, Apds9960Drv};
 //!
-//! use smartoris_apds9960::Apds9960Drv;
-//!
-//! let mut apds9960 = Apds9960Drv::init();
+//! let mut apds9960 = Apds9960Drv::<Adapters>::init();
 //! apds9960.store_enable(&mut i2c1, |r| r.set_pon().set_pen()).await.into_ok();
 //! loop {
 //!     if apds9960.load_status(&mut i2c1).await.into_ok().pvalid() {
@@ -120,12 +81,30 @@
 //! # fn main() {}
 //! ```
 //!
+//! Other I²C implementations need an [`Apds9960I2CPort`] implementation with
+//! a local marker type. Refer to `src/adapters/smartoris_i2c.rs` for an
+//! example.
+//!
+//! # Host tools
+//!
+//! With `std` feature enabled on Linux, [`adapters::LinuxI2C`] drives the
+//! sensor through `/dev/i2c-N`. The crate also provides an `apds9960` binary
+//! to probe the device, dump and set registers, and stream measurements:
+//!
+//! ```sh
+//! cargo run --features std --bin apds9960 -- --device /dev/i2c-1 dump
+//! ```
+//!
+//! Pass `--sim` instead of `--device` to run against the in-memory
+//! [`adapters::Apds9960Sim`] simulator.
+//!
 //! # References
 //!
 //! * [Datasheet](https://docs.broadcom.com/doc/AV02-4191EN)
 //!
 //! [Drone OS]: https://www.drone-os.com/
 
+#![feature(never_type)]
 #![feature(prelude_import)]
 #![warn(missing_docs)]
 #![warn(clippy::pedantic)]
@@ -135,12 +114,48 @@
 #[macro_use]
 extern crate alloc;
 
+pub mod adapters;
+pub mod power;
 pub mod reg;
+pub mod timing;
 
+mod array;
+mod batch;
 mod drv;
+mod dump;
+mod error;
+mod int;
+mod measure;
+mod owned;
 mod ports;
+mod self_test;
+mod split;
+mod stats;
+mod supervisor;
+mod sync;
+mod typestate;
+mod verify;
+mod window;
 
-pub use self::{drv::Apds9960Drv, ports::i2c::Apds9960I2CPort};
+pub use self::{
+    array::Apds9960Array,
+    batch::RegisterBatch,
+    drv::Apds9960Drv,
+    dump::RegisterDump,
+    error::Apds9960Error,
+    int::IntWiringReport,
+    measure::{Rgbc, MIN_POLL_INTERVAL, POWER_ON_DELAY},
+    owned::Apds9960Owned,
+    ports::{
+        delay::Apds9960DelayPort, i2c::Apds9960I2CPort, int::Apds9960IntPort, retry::RetryableError,
+    },
+    self_test::{SelfTestItem, SelfTestReport},
+    split::{AlsPart, GesturePart, ProximityPart},
+    stats::BusStats,
+    supervisor::{DeviceReset, Supervisor},
+    typestate::{AlsEngine, Apds9960, GestureEngine, Off, On, ProximityEngine},
+    verify::Apds9960Verified,
+};
 
 /// Default APDS-9960 I²C slave address.
 pub const DEFAULT_ADDR: u8 = 0x39;
@@ -0,0 +1,277 @@
+use crate::{
+    reg::{Enable, Status},
+    timing::Config,
+    Apds9960DelayPort, Apds9960Drv, Apds9960Error, Apds9960I2CPort,
+};
+use core::time::Duration;
+
+/// Time the device needs after `Enable::pon` is set before the first cycle.
+pub const POWER_ON_DELAY: Duration = Duration::from_micros(5700);
+
+/// Minimum interval between STATUS checks in
+/// [`wait_status`](Apds9960Drv::wait_status).
+pub const MIN_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// CDATA register address.
+const CDATA: u8 = 0x94;
+
+/// ENABLE register address, the start of the timing registers.
+const ENABLE: u8 = 0x80;
+
+/// Number of registers from ENABLE to CONFIG2.
+const TIMING_SIZE: usize = 0x11;
+
+/// GCONF2 register address, the start of the gesture timing registers.
+const GCONF2: u8 = 0xA3;
+
+/// Number of registers from GCONF2 to GPULSE.
+const GESTURE_TIMING_SIZE: usize = 4;
+
+/// RGBC measurement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rgbc {
+    /// Clear channel.
+    pub clear: u16,
+    /// Red channel.
+    pub red: u16,
+    /// Green channel.
+    pub green: u16,
+    /// Blue channel.
+    pub blue: u16,
+}
+
+impl<A> Apds9960Drv<A> {
+    /// Reads CDATA, RDATA, GDATA and BDATA registers in one burst.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn load_rgbc<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+    ) -> Result<Rgbc, P::Error> {
+        let data = self.load_burst(i2c, CDATA, 8).await?;
+        let channel = |i: usize| u16::from_le_bytes([data[i * 2], data[i * 2 + 1]]);
+        Ok(Rgbc { clear: channel(0), red: channel(1), green: channel(2), blue: channel(3) })
+    }
+
+    /// Performs a single RGBC measurement.
+    ///
+    /// Powers the device on and enables only the ALS engine, with the
+    /// proximity, gesture and wait states disabled, then waits for
+    /// `Status::avalid` with [`wait_status`](Apds9960Drv::wait_status) and
+    /// reads the data with [`load_rgbc`](Apds9960Drv::load_rgbc). Finally,
+    /// writes back the original contents of ENABLE register, so the device
+    /// returns to sleep if it was powered off.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Apds9960Error::Timeout`] if no data is available within
+    /// `timeout`. If `i2c` implementation returns `Err`, it's propagated as
+    /// [`Apds9960Error::Port`].
+    pub async fn measure_rgbc_once<P, D>(
+        &mut self,
+        i2c: &mut P,
+        delay: &mut D,
+        timeout: Duration,
+    ) -> Result<Rgbc, Apds9960Error<P::Error>>
+    where
+        P: Apds9960I2CPort<A>,
+        D: Apds9960DelayPort<A>,
+    {
+        self.measure_rgbc_status(i2c, delay, timeout).await.map(|(rgbc, _)| rgbc)
+    }
+
+    /// Performs [`measure_rgbc_once`](Apds9960Drv::measure_rgbc_once), also
+    /// returning STATUS register as it was when the data became valid.
+    pub(crate) async fn measure_rgbc_status<P, D>(
+        &mut self,
+        i2c: &mut P,
+        delay: &mut D,
+        timeout: Duration,
+    ) -> Result<(Rgbc, Status), Apds9960Error<P::Error>>
+    where
+        P: Apds9960I2CPort<A>,
+        D: Apds9960DelayPort<A>,
+    {
+        let enable = self.power_up(i2c, delay, |r| r.set_aen()).await?;
+        let result = match self.wait_status(i2c, delay, timeout, Status::avalid).await {
+            Ok(status) => {
+                self.load_rgbc(i2c).await.map(|rgbc| (rgbc, status)).map_err(Apds9960Error::Port)
+            }
+            Err(err) => Err(err),
+        };
+        self.store_enable_val(i2c, enable).await.map_err(Apds9960Error::Port)?;
+        result
+    }
+
+    /// Performs a single proximity measurement.
+    ///
+    /// Powers the device on and enables only the proximity engine, with the
+    /// ALS, gesture and wait states disabled, then waits for
+    /// `Status::pvalid` with [`wait_status`](Apds9960Drv::wait_status) and
+    /// reads PDATA. Finally, writes back the original contents of ENABLE
+    /// register, so the device returns to sleep if it was powered off.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Apds9960Error::Timeout`] if no data is available within
+    /// `timeout`. If `i2c` implementation returns `Err`, it's propagated as
+    /// [`Apds9960Error::Port`].
+    pub async fn measure_proximity_once<P, D>(
+        &mut self,
+        i2c: &mut P,
+        delay: &mut D,
+        timeout: Duration,
+    ) -> Result<u8, Apds9960Error<P::Error>>
+    where
+        P: Apds9960I2CPort<A>,
+        D: Apds9960DelayPort<A>,
+    {
+        self.measure_proximity_status(i2c, delay, timeout).await.map(|(pdata, _)| pdata)
+    }
+
+    /// Performs
+    /// [`measure_proximity_once`](Apds9960Drv::measure_proximity_once), also
+    /// returning STATUS register as it was when the data became valid.
+    pub(crate) async fn measure_proximity_status<P, D>(
+        &mut self,
+        i2c: &mut P,
+        delay: &mut D,
+        timeout: Duration,
+    ) -> Result<(u8, Status), Apds9960Error<P::Error>>
+    where
+        P: Apds9960I2CPort<A>,
+        D: Apds9960DelayPort<A>,
+    {
+        let enable = self.power_up(i2c, delay, |r| r.set_pen()).await?;
+        let result = match self.wait_status(i2c, delay, timeout, Status::pvalid).await {
+            Ok(status) => {
+                self.load_pdata(i2c).await.map(|pdata| (pdata, status)).map_err(Apds9960Error::Port)
+            }
+            Err(err) => Err(err),
+        };
+        self.store_enable_val(i2c, enable).await.map_err(Apds9960Error::Port)?;
+        result
+    }
+
+    /// Waits until `f` returns `true` for STATUS register, and returns the
+    /// register value.
+    ///
+    /// Reads the timing registers to compute the cycle time, then sleeps with
+    /// `delay` for one cycle, but at least [`MIN_POLL_INTERVAL`], before each
+    /// STATUS check.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Apds9960Error::Idle`] without polling if the device is
+    /// powered off or no engine is enabled. Returns
+    /// [`Apds9960Error::Timeout`] if `f` doesn't return `true` within
+    /// `timeout`. If `i2c` implementation returns `Err`, it's propagated as
+    /// [`Apds9960Error::Port`].
+    pub async fn wait_status<P, D>(
+        &mut self,
+        i2c: &mut P,
+        delay: &mut D,
+        timeout: Duration,
+        f: impl Fn(&Status) -> bool,
+    ) -> Result<Status, Apds9960Error<P::Error>>
+    where
+        P: Apds9960I2CPort<A>,
+        D: Apds9960DelayPort<A>,
+    {
+        let cycle = self.cycle_time(i2c).await.map_err(Apds9960Error::Port)?;
+        if cycle == Duration::default() {
+            return Err(Apds9960Error::Idle);
+        }
+        let cycle = cycle.max(MIN_POLL_INTERVAL);
+        let mut deadline = Deadline::new(delay, timeout);
+        loop {
+            delay.delay(cycle).await;
+            deadline.advance(cycle);
+            let status = self.load_status(i2c).await.map_err(Apds9960Error::Port)?;
+            if f(&status) {
+                return Ok(status);
+            }
+            if deadline.expired(delay) {
+                return Err(Apds9960Error::Timeout);
+            }
+        }
+    }
+
+    /// Reads the timing registers and returns the total cycle time. Gesture
+    /// registers are read only if the gesture engine is enabled.
+    async fn cycle_time<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+    ) -> Result<Duration, P::Error> {
+        let regs = self.load_burst(i2c, ENABLE, TIMING_SIZE).await?;
+        let mut config = Config {
+            enable: regs[0x0].into(),
+            atime: regs[0x1],
+            wtime: regs[0x3],
+            config1: regs[0xD].into(),
+            ppulse: regs[0xE].into(),
+            control: regs[0xF].into(),
+            config2: regs[0x10].into(),
+            ..Config::default()
+        };
+        if config.enable.pon() && config.enable.gen() {
+            let regs = self.load_burst(i2c, GCONF2, GESTURE_TIMING_SIZE).await?;
+            config.gconf2 = regs[0].into();
+            config.gpulse = regs[3].into();
+        }
+        Ok(config.cycle_time().total())
+    }
+
+    /// Sets `Enable::pon` and the engine bit set by `f`, clearing the other
+    /// engine and wait bits, sleeps for the power-on delay if the device was
+    /// off, and returns the original ENABLE.
+    async fn power_up<P, D>(
+        &mut self,
+        i2c: &mut P,
+        delay: &mut D,
+        f: impl FnOnce(&mut Enable) -> &mut Enable,
+    ) -> Result<Enable, Apds9960Error<P::Error>>
+    where
+        P: Apds9960I2CPort<A>,
+        D: Apds9960DelayPort<A>,
+    {
+        let enable = self.load_enable(i2c).await.map_err(Apds9960Error::Port)?;
+        let mut value = enable;
+        f(value.clear_aen().clear_pen().clear_gen().clear_wen().set_pon());
+        self.store_enable_val(i2c, value).await.map_err(Apds9960Error::Port)?;
+        if !enable.pon() {
+            delay.delay(POWER_ON_DELAY).await;
+        }
+        Ok(enable)
+    }
+}
+
+/// Timeout tracking that uses [`Apds9960DelayPort::now`] when available, and
+/// counts the requested delays otherwise.
+pub(crate) struct Deadline {
+    start: Option<Duration>,
+    elapsed: Duration,
+    timeout: Duration,
+}
+
+impl Deadline {
+    pub(crate) fn new<A, D: Apds9960DelayPort<A>>(delay: &mut D, timeout: Duration) -> Self {
+        Self { start: delay.now(), elapsed: Duration::default(), timeout }
+    }
+
+    pub(crate) fn advance(&mut self, duration: Duration) {
+        self.elapsed += duration;
+    }
+
+    pub(crate) fn expired<A, D: Apds9960DelayPort<A>>(&self, delay: &mut D) -> bool {
+        let elapsed = match (self.start, delay.now()) {
+            (Some(start), Some(now)) => now.checked_sub(start).unwrap_or_default(),
+            _ => self.elapsed,
+        };
+        elapsed >= self.timeout
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{
+    reg::{ReadableRegister, WritableRegister},
+    Apds9960Drv, Apds9960I2CPort, RegisterBatch, RegisterDump, Rgbc,
+};
+
+/// APDS-9960 driver that owns its I²C port.
+///
+/// Exposes the same register accessors as [`Apds9960Drv`], without the `i2c`
+/// parameter. The port can be owned, or borrowed by passing `&mut P`:
+///
+/// ```
+/// # use smartoris_apds9960::{Apds9960Drv, Apds9960I2CPort, Apds9960Owned};
+/// # async fn f<P: Apds9960I2CPort<()> + Send>(i2c: &mut P) -> Result<(), P::Error> {
+/// let mut apds9960 = Apds9960Owned::new(Apds9960Drv::<()>::init(), i2c);
+/// let id = apds9960.load_id().await?;
+/// apds9960.store_enable(|r| r.set_pon()).await?;
+/// let (drv, i2c) = apds9960.release();
+/// # Ok(())
+/// # }
+/// ```
+pub struct Apds9960Owned<A, P> {
+    pub(crate) drv: Apds9960Drv<A>,
+    pub(crate) i2c: P,
+}
+
+impl<A, P: Apds9960I2CPort<A>> Apds9960Owned<A, P> {
+    /// Binds `drv` to `i2c`.
+    pub fn new(drv: Apds9960Drv<A>, i2c: P) -> Self {
+        Self { drv, i2c }
+    }
+
+    /// Releases the driver and the port.
+    pub fn release(self) -> (Apds9960Drv<A>, P) {
+        (self.drv, self.i2c)
+    }
+
+    /// Returns the driver and the port for calls that take them separately.
+    pub fn parts(&mut self) -> (&mut Apds9960Drv<A>, &mut P) {
+        (&mut self.drv, &mut self.i2c)
+    }
+
+    /// Changes the I²C slave address.
+    pub fn set_addr(&mut self, addr: u8) {
+        self.drv.set_addr(addr);
+    }
+
+    /// Reads contents of `R` register.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn load<R: ReadableRegister>(&mut self) -> Result<R, P::Error> {
+        self.drv.load(&mut self.i2c).await
+    }
+
+    /// Writes `value` to `R` register.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn store<R: WritableRegister>(&mut self, value: R) -> Result<(), P::Error> {
+        self.drv.store(&mut self.i2c, value).await
+    }
+
+    /// Reads contents of `R` register, passes it to the closure `f`, then
+    /// writes the result of the closure back to the register.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn modify<R, F>(&mut self, f: F) -> Result<(), P::Error>
+    where
+        R: ReadableRegister + WritableRegister,
+        F: FnOnce(&mut R) -> &mut R,
+    {
+        self.drv.modify(&mut self.i2c, f).await
+    }
+
+    /// Writes all registers from `batch` using auto-increment burst writes.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn store_batch(&mut self, batch: &RegisterBatch) -> Result<(), P::Error> {
+        self.drv.store_batch(&mut self.i2c, batch).await
+    }
+
+    /// Reads CDATA, RDATA, GDATA and BDATA registers in one burst.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn load_rgbc(&mut self) -> Result<Rgbc, P::Error> {
+        self.drv.load_rgbc(&mut self.i2c).await
+    }
+
+    /// Performs a page read of `level` number of gesture datasets from FIFO.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn drain_fifo(&mut self, level: u8) -> Result<&[u8], P::Error> {
+        self.drv.drain_fifo(&mut self.i2c, level).await
+    }
+
+    /// Reads all registers, and the gesture FIFO if `gfifo` is `true`.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn dump_registers(&mut self, gfifo: bool) -> Result<RegisterDump, P::Error> {
+        self.drv.dump_registers(&mut self.i2c, gfifo).await
+    }
+}
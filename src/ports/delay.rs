@@ -0,0 +1,21 @@
+use async_trait::async_trait;
+use core::time::Duration;
+
+/// Delay port for APDS-9960.
+///
+/// Lets the driver sleep for power-on and integration times instead of
+/// polling the device over I²C.
+#[async_trait]
+pub trait Apds9960DelayPort<A> {
+    /// Completes after at least `duration` has elapsed.
+    async fn delay(&mut self, duration: Duration);
+
+    /// Returns the current time of a monotonic clock, if available.
+    ///
+    /// Used to measure timeouts. When `None` is returned, the driver counts
+    /// the requested delays instead, which doesn't account for the I²C
+    /// transfer time.
+    fn now(&mut self) -> Option<Duration> {
+        None
+    }
+}
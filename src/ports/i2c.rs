@@ -30,4 +30,42 @@ pub trait Apds9960I2CPort<A> {
         buf: Box<[u8]>,
         count: usize,
     ) -> Result<Box<[u8]>, (Box<[u8]>, Self::Error)>;
+
+    /// Returns the number of retries performed since the last call and
+    /// resets it.
+    ///
+    /// The driver calls this after each transaction to count
+    /// [`BusStats::retries`](crate::BusStats::retries). The default
+    /// implementation, for ports that don't retry, returns `0`. Port wrappers
+    /// should forward it to the inner port.
+    fn take_retries(&mut self) -> u32 {
+        0
+    }
+}
+
+#[async_trait]
+impl<A, P: Apds9960I2CPort<A> + Send> Apds9960I2CPort<A> for &mut P {
+    type Error = P::Error;
+
+    async fn write(
+        &mut self,
+        addr: u8,
+        buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, Self::Error)> {
+        (**self).write(addr, buf, count).await
+    }
+
+    async fn read(
+        &mut self,
+        addr: u8,
+        buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, Self::Error)> {
+        (**self).read(addr, buf, count).await
+    }
+
+    fn take_retries(&mut self) -> u32 {
+        (**self).take_retries()
+    }
 }
@@ -0,0 +1,18 @@
+use async_trait::async_trait;
+
+/// Interrupt line port for APDS-9960.
+///
+/// The INT pin is an active-low open-drain output, so the line is high while
+/// no interrupt is pending, provided that the board has a pull-up.
+#[async_trait]
+pub trait Apds9960IntPort<A> {
+    /// Returns `true` if the INT line is currently low.
+    fn is_low(&mut self) -> bool;
+
+    /// Completes when the INT line is low.
+    ///
+    /// Level-triggered implementations complete immediately if the line is
+    /// already low. Edge-triggered implementations complete on the next
+    /// falling edge.
+    async fn wait_low(&mut self);
+}
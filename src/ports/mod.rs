@@ -1 +1,4 @@
+pub(crate) mod delay;
 pub(crate) mod i2c;
+pub(crate) mod int;
+pub(crate) mod retry;
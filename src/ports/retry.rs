@@ -0,0 +1,16 @@
+/// Classification of I²C port errors.
+///
+/// Used by [`Retry`](crate::adapters::Retry) to decide whether a failed
+/// transaction is worth repeating. Transient conditions, like a missing
+/// acknowledge or a lost arbitration, should return `true`, while
+/// configuration errors should return `false`.
+pub trait RetryableError {
+    /// Returns `true` if the failed transaction can be repeated.
+    fn is_retryable(&self) -> bool;
+}
+
+impl RetryableError for ! {
+    fn is_retryable(&self) -> bool {
+        *self
+    }
+}
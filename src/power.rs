@@ -0,0 +1,91 @@
+//! Power consumption estimation.
+//!
+//! Estimates are averaged over one measurement cycle as computed by
+//! [`timing`](crate::timing), using typical supply currents from the
+//! datasheet. They are meant for comparing configurations, not for exact
+//! battery life predictions.
+
+use crate::timing::{micros, Config, PULSE_LENGTH};
+
+/// Typical supply current in sleep state, in microamperes.
+pub const SLEEP_CURRENT: u32 = 1;
+
+/// Typical supply current in wait and idle states, in microamperes.
+pub const WAIT_CURRENT: u32 = 38;
+
+/// Typical supply current in ALS, proximity and gesture states, in
+/// microamperes.
+pub const ACTIVE_CURRENT: u32 = 200;
+
+/// Typical supply current while the LED driver is pulsing, in microamperes.
+pub const PULSE_CURRENT: u32 = 790;
+
+/// LED currents for LDRIVE and GLDRIVE fields, in microamperes.
+const LED_DRIVE: [u32; 4] = [100_000, 50_000, 25_000, 12_500];
+
+/// LED current boost for `LED_BOOST` field, in percents.
+const LED_BOOST: [u32; 4] = [100, 150, 200, 300];
+
+/// Estimated average currents of a configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PowerEstimate {
+    /// Average device supply current, excluding the LED, in microamperes.
+    pub supply: u32,
+    /// Average LED current, in microamperes.
+    pub led: u32,
+}
+
+impl PowerEstimate {
+    /// Returns the total average current, in microamperes.
+    #[must_use]
+    pub fn total(self) -> u32 {
+        self.supply + self.led
+    }
+}
+
+/// Estimates the average current of `config`.
+///
+/// Takes into account the enabled engines, ATIME, WTIME and WLONG, pulse
+/// counts and lengths, LED drive strengths and LED boost.
+#[must_use]
+pub fn estimate(config: Config) -> PowerEstimate {
+    if !config.enable.pon() {
+        return PowerEstimate { supply: SLEEP_CURRENT, led: 0 };
+    }
+    let cycle = config.cycle_time();
+    let total = micros(cycle.total());
+    if total == 0 {
+        return PowerEstimate { supply: WAIT_CURRENT, led: 0 };
+    }
+    let boost = LED_BOOST[usize::from(config.config2.led_boost())];
+    let mut pulse_time = 0;
+    let mut led_charge = 0;
+    if config.enable.pen() {
+        let time = pulse_time_of(config.ppulse.ppulse(), config.ppulse.pplen());
+        pulse_time += time;
+        led_charge += u64::from(time) * led_current(config.control.ldrive(), boost);
+    }
+    if config.enable.gen() {
+        let time = pulse_time_of(config.gpulse.gpulse(), config.gpulse.gplen());
+        pulse_time += time;
+        led_charge += u64::from(time) * led_current(config.gconf2.gldrive(), boost);
+    }
+    let active = micros(cycle.proximity + cycle.gesture + cycle.als);
+    let supply_charge = u64::from(active.saturating_sub(pulse_time)) * u64::from(ACTIVE_CURRENT)
+        + u64::from(pulse_time) * u64::from(PULSE_CURRENT)
+        + u64::from(micros(cycle.wait)) * u64::from(WAIT_CURRENT);
+    PowerEstimate { supply: average(supply_charge, total), led: average(led_charge, total) }
+}
+
+fn pulse_time_of(count: u8, length: u8) -> u32 {
+    (u32::from(count) + 1) * PULSE_LENGTH[usize::from(length)]
+}
+
+fn led_current(drive: u8, boost: u32) -> u64 {
+    u64::from(LED_DRIVE[usize::from(drive)] * boost / 100)
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn average(charge: u64, time: u32) -> u32 {
+    (charge / u64::from(time)) as u32
+}
@@ -1,19 +1,41 @@
 macro_rules! apds9960_reg {
     (
-        $(#[$($attr:meta)*])*
-        $name:ident $type:ident $size:literal $addr:literal $reset:literal
+        $(#[doc = $doc:literal])*
+        $name:ident $label:literal $type:ident $size:literal $addr:literal $reset:literal
         $mode:ident { $($mode_tt:tt)* }
         [ $($field:ident($($field_tt:tt)*),)* ]
     ) => {
-        $(#[$($attr)*])*
+        $(#[doc = $doc])*
         #[derive(Clone, Copy, Bitfield)]
+        #[cfg_attr(feature = "defmt", derive(defmt::Format))]
         #[bitfield($($field($mode, $($field_tt)*),)*)]
         pub struct $name($type);
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($name))
+                    $(.field(stringify!($field), &self.$field()))*
+                    .finish()
+            }
+        }
         impl Default for $name {
             fn default() -> Self {
                 $name($reset)
             }
         }
+        impl From<$type> for $name {
+            fn from(bits: $type) -> Self {
+                $name(bits)
+            }
+        }
+        impl From<$name> for $type {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+        apds9960_reg_trait! {
+            $name $label $type $size $addr $reset $mode
+            concat!($($doc),*), [$($field($($field_tt)*),)*]
+        }
         apds9960_reg!($name $type $size $addr $mode { $($mode_tt)* });
     };
 
@@ -31,6 +53,12 @@ macro_rules! apds9960_reg {
                 self.load_reg(i2c, $addr, $size).map(|x| x.map(|x| $name(x as $type)))
             }
         }
+        impl<A, P: Apds9960I2CPort<A>> Apds9960Owned<A, P> {
+            #[$($load_attr)*]
+            pub fn $load(&mut self) -> impl Future<Output = Result<$name, P::Error>> + '_ {
+                self.drv.$load(&mut self.i2c)
+            }
+        }
     };
 
     (
@@ -58,6 +86,42 @@ macro_rules! apds9960_reg {
                 self.store_reg(i2c, u16::from(value), $addr, $size)
             }
         }
+        impl<A, P: Apds9960I2CPort<A>> Apds9960Owned<A, P> {
+            $(#[$($store_val_attr)*])*
+            pub fn $store_val(
+                &mut self,
+                value: $name,
+            ) -> impl Future<Output = Result<(), P::Error>> + '_ {
+                self.drv.$store_val(&mut self.i2c, value)
+            }
+            $(#[$($store_attr)*])*
+            pub fn $store(
+                &mut self,
+                f: impl FnOnce(&mut $name) -> &mut $name,
+            ) -> impl Future<Output = Result<(), P::Error>> + '_ {
+                let value = *f(&mut $name::default());
+                self.drv.$store_val(&mut self.i2c, value)
+            }
+        }
+        impl<A> Apds9960Verified<'_, A> {
+            $(#[$($store_val_attr)*])*
+            pub fn $store_val<'a, P: Apds9960I2CPort<A>>(
+                &'a mut self,
+                i2c: &'a mut P,
+                value: $name,
+            ) -> impl Future<Output = Result<(), Apds9960Error<P::Error>>> + 'a {
+                self.drv.store_verified(i2c, value)
+            }
+            $(#[$($store_attr)*])*
+            pub fn $store<'a, P: Apds9960I2CPort<A>>(
+                &'a mut self,
+                i2c: &'a mut P,
+                f: impl FnOnce(&mut $name) -> &mut $name,
+            ) -> impl Future<Output = Result<(), Apds9960Error<P::Error>>> + 'a {
+                let value = *f(&mut $name::default());
+                self.drv.store_verified(i2c, value)
+            }
+        }
     };
 
     (
@@ -83,7 +147,38 @@ macro_rules! apds9960_reg {
 
 macro_rules! apds9960_reg_raw {
     (
-        $type:ident $size:literal $addr:literal r {
+        $(#[doc = $doc:literal])*
+        $name:ident $label:literal $type:ident $size:literal $addr:literal $reset:literal
+        $mode:ident { $($mode_tt:tt)* }
+    ) => {
+        $(#[doc = $doc])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+        pub struct $name(pub $type);
+        impl Default for $name {
+            fn default() -> Self {
+                $name($reset)
+            }
+        }
+        impl From<$type> for $name {
+            fn from(bits: $type) -> Self {
+                $name(bits)
+            }
+        }
+        impl From<$name> for $type {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+        apds9960_reg_trait! {
+            $name $label $type $size $addr $reset $mode
+            concat!($($doc),*), []
+        }
+        apds9960_reg_raw!($name $type $size $addr $mode { $($mode_tt)* });
+    };
+
+    (
+        $name:ident $type:ident $size:literal $addr:literal r {
             $(#[$($load_attr:meta)*])* fn $load:ident;
         }
     ) => {
@@ -96,10 +191,16 @@ macro_rules! apds9960_reg_raw {
                 self.load_reg(i2c, $addr, $size).map(|x| x.map(|x| x as $type))
             }
         }
+        impl<A, P: Apds9960I2CPort<A>> Apds9960Owned<A, P> {
+            $(#[$($load_attr)*])*
+            pub fn $load(&mut self) -> impl Future<Output = Result<$type, P::Error>> + '_ {
+                self.drv.$load(&mut self.i2c)
+            }
+        }
     };
 
     (
-        $type:ident $size:literal $addr:literal w {
+        $name:ident $type:ident $size:literal $addr:literal w {
             $(#[$($store_attr:meta)*])* fn $store:ident;
         }
     ) => {
@@ -113,27 +214,96 @@ macro_rules! apds9960_reg_raw {
                 self.store_reg(i2c, u16::from(value), $addr, $size)
             }
         }
+        impl<A, P: Apds9960I2CPort<A>> Apds9960Owned<A, P> {
+            $(#[$($store_attr)*])*
+            pub fn $store(&mut self, value: $type) -> impl Future<Output = Result<(), P::Error>> + '_ {
+                self.drv.$store(&mut self.i2c, value)
+            }
+        }
+        impl<A> Apds9960Verified<'_, A> {
+            $(#[$($store_attr)*])*
+            pub fn $store<'a, P: Apds9960I2CPort<A>>(
+                &'a mut self,
+                i2c: &'a mut P,
+                value: $type,
+            ) -> impl Future<Output = Result<(), Apds9960Error<P::Error>>> + 'a {
+                self.drv.store_verified(i2c, $name(value))
+            }
+        }
     };
 
     (
-        $type:ident $size:literal $addr:literal rw {
+        $name:ident $type:ident $size:literal $addr:literal rw {
             $(#[$($load_attr:meta)*])* fn $load:ident;
             $(#[$($store_attr:meta)*])* fn $store:ident;
         }
     ) => {
         apds9960_reg_raw! {
-            $type $size $addr r {
+            $name $type $size $addr r {
                 $(#[$($load_attr)*])* fn $load;
             }
         }
         apds9960_reg_raw! {
-            $type $size $addr w {
+            $name $type $size $addr w {
                 $(#[$($store_attr)*])* fn $store;
             }
         }
     };
 }
 
+macro_rules! apds9960_reg_trait {
+    (
+        $name:ident $label:literal $type:ident $size:literal $addr:literal $reset:literal
+        $mode:ident $description:expr, [$($field:ident($($field_tt:tt)*),)*]
+    ) => {
+        impl Register for $name {
+            const ACCESS: Access = apds9960_reg_trait!(@access $mode);
+            const ADDR: u8 = $addr;
+            const DESCRIPTION: &'static str = $description;
+            const FIELDS: &'static [FieldInfo] = &[
+                $(apds9960_reg_trait!(@field $field $($field_tt)*),)*
+            ];
+            const NAME: &'static str = $label;
+            const RESET: u16 = $reset;
+            const SIZE: usize = $size;
+
+            #[allow(clippy::cast_possible_truncation)]
+            fn from_raw(raw: u16) -> Self {
+                $name(raw as $type)
+            }
+
+            fn into_raw(self) -> u16 {
+                u16::from(self.0)
+            }
+        }
+        apds9960_reg_trait!(@marker $name $mode);
+    };
+    (@field $field:ident $offset:literal, $description:literal) => {
+        FieldInfo { name: stringify!($field), offset: $offset, width: 1, description: $description }
+    };
+    (@field $field:ident $offset:literal, $width:literal, $description:literal) => {
+        FieldInfo {
+            name: stringify!($field),
+            offset: $offset,
+            width: $width,
+            description: $description,
+        }
+    };
+    (@access r) => { Access::Read };
+    (@access w) => { Access::Write };
+    (@access rw) => { Access::ReadWrite };
+    (@marker $name:ident r) => {
+        impl ReadableRegister for $name {}
+    };
+    (@marker $name:ident w) => {
+        impl WritableRegister for $name {}
+    };
+    (@marker $name:ident rw) => {
+        impl ReadableRegister for $name {}
+        impl WritableRegister for $name {}
+    };
+}
+
 macro_rules! apds9960_reg_touch {
     (
         $addr:literal;
@@ -149,5 +319,11 @@ macro_rules! apds9960_reg_touch {
                 self.touch_reg(i2c, $addr)
             }
         }
+        impl<A, P: Apds9960I2CPort<A>> Apds9960Owned<A, P> {
+            $(#[$($attr)*])*
+            pub fn $name(&mut self) -> impl Future<Output = Result<(), P::Error>> + '_ {
+                self.drv.$name(&mut self.i2c)
+            }
+        }
     };
 }
@@ -0,0 +1,208 @@
+use super::{
+    Access, Aiht, Ailt, Atime, Bdata, Cdata, Config1, Config2, Config3, Control, Enable, Gconf1,
+    Gconf2, Gconf3, Gconf4, Gdata, Gexth, GfifoD, GfifoL, GfifoR, GfifoU, Gflvl, GoffsetD,
+    GoffsetL, GoffsetR, GoffsetU, Gpenth, Gpulse, Gstatus, Id, Pdata, Pers, Piht, Pilt, PoffsetDl,
+    PoffsetUr, Ppulse, Rdata, Register, Status, Wtime,
+};
+
+/// Register bitfield description.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldInfo {
+    pub(crate) name: &'static str,
+    pub(crate) offset: u8,
+    pub(crate) width: u8,
+    pub(crate) description: &'static str,
+}
+
+/// Register description.
+#[derive(Debug, Clone, Copy)]
+pub struct RegisterInfo {
+    pub(crate) name: &'static str,
+    pub(crate) addr: u8,
+    pub(crate) size: usize,
+    pub(crate) access: Access,
+    pub(crate) reset: u16,
+    pub(crate) description: &'static str,
+    pub(crate) fields: &'static [FieldInfo],
+}
+
+/// Descriptions of all APDS-9960 registers ordered by address.
+///
+/// Address-only registers (IFORCE, PICLEAR, CICLEAR, AICLEAR) have zero size
+/// and [`Access::Write`] access.
+pub static REGISTER_MAP: &[RegisterInfo] = &[
+    Enable::INFO,
+    Atime::INFO,
+    Wtime::INFO,
+    Ailt::INFO,
+    Aiht::INFO,
+    Pilt::INFO,
+    Piht::INFO,
+    Pers::INFO,
+    Config1::INFO,
+    Ppulse::INFO,
+    Control::INFO,
+    Config2::INFO,
+    Id::INFO,
+    Status::INFO,
+    Cdata::INFO,
+    Rdata::INFO,
+    Gdata::INFO,
+    Bdata::INFO,
+    Pdata::INFO,
+    PoffsetUr::INFO,
+    PoffsetDl::INFO,
+    Config3::INFO,
+    Gpenth::INFO,
+    Gexth::INFO,
+    Gconf1::INFO,
+    Gconf2::INFO,
+    GoffsetU::INFO,
+    GoffsetD::INFO,
+    Gpulse::INFO,
+    GoffsetL::INFO,
+    GoffsetR::INFO,
+    Gconf3::INFO,
+    Gconf4::INFO,
+    Gflvl::INFO,
+    Gstatus::INFO,
+    RegisterInfo::touch("IFORCE", 0xE4, "Force interrupt."),
+    RegisterInfo::touch("PICLEAR", 0xE5, "Proximity interrupt clear."),
+    RegisterInfo::touch("CICLEAR", 0xE6, "ALS clear channel interrupt clear."),
+    RegisterInfo::touch("AICLEAR", 0xE7, "All non-gesture interrupts clear."),
+    GfifoU::INFO,
+    GfifoD::INFO,
+    GfifoL::INFO,
+    GfifoR::INFO,
+];
+
+impl FieldInfo {
+    /// Returns the field name.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the offset of the least significant bit.
+    #[must_use]
+    pub fn offset(&self) -> u8 {
+        self.offset
+    }
+
+    /// Returns the field width in bits.
+    #[must_use]
+    pub fn width(&self) -> u8 {
+        self.width
+    }
+
+    /// Returns the field description.
+    #[must_use]
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+
+    /// Returns the bit mask of the field.
+    #[must_use]
+    pub fn mask(&self) -> u16 {
+        ((1 << self.width) - 1) << self.offset
+    }
+
+    /// Extracts the field value from raw register `bits`.
+    #[must_use]
+    pub fn extract(&self, bits: u16) -> u16 {
+        (bits & self.mask()) >> self.offset
+    }
+}
+
+impl RegisterInfo {
+    const fn touch(name: &'static str, addr: u8, description: &'static str) -> Self {
+        Self { name, addr, size: 0, access: Access::Write, reset: 0, description, fields: &[] }
+    }
+
+    /// Finds a register by its datasheet name, ignoring ASCII case.
+    #[must_use]
+    pub fn by_name(name: &str) -> Option<&'static Self> {
+        REGISTER_MAP.iter().find(|info| info.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Finds a register by its address.
+    ///
+    /// Only the first address of a multi-byte register matches.
+    #[must_use]
+    pub fn by_addr(addr: u8) -> Option<&'static Self> {
+        REGISTER_MAP.iter().find(|info| info.addr == addr)
+    }
+
+    /// Returns the register name as in the datasheet.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the register address.
+    #[must_use]
+    pub fn addr(&self) -> u8 {
+        self.addr
+    }
+
+    /// Returns the register size in bytes.
+    #[must_use]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the register access mode.
+    #[must_use]
+    pub fn access(&self) -> Access {
+        self.access
+    }
+
+    /// Returns the register reset value.
+    #[must_use]
+    pub fn reset(&self) -> u16 {
+        self.reset
+    }
+
+    /// Returns the register description.
+    #[must_use]
+    pub fn description(&self) -> &'static str {
+        self.description.trim()
+    }
+
+    /// Returns the register bitfields.
+    ///
+    /// Empty for registers holding a single value.
+    #[must_use]
+    pub fn fields(&self) -> &'static [FieldInfo] {
+        self.fields
+    }
+
+    /// Returns `true` if the register can be read.
+    #[must_use]
+    pub fn is_readable(&self) -> bool {
+        self.access != Access::Write
+    }
+
+    /// Returns `true` if the register can be written.
+    #[must_use]
+    pub fn is_writable(&self) -> bool {
+        self.access != Access::Read
+    }
+
+    /// Checks whether `value` can be written to the register.
+    ///
+    /// The value must fit into the register size, and for registers with
+    /// bitfields, it must not set bits outside of the fields and the reset
+    /// value.
+    #[must_use]
+    pub fn validate(&self, value: u16) -> bool {
+        if !self.is_writable() || u32::from(value) >> (self.size * 8) != 0 {
+            return false;
+        }
+        if self.fields.is_empty() {
+            return true;
+        }
+        let mask = self.fields.iter().fold(self.reset, |mask, field| mask | field.mask());
+        value & !mask == 0
+    }
+}
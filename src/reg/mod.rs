@@ -3,13 +3,39 @@
 #[macro_use]
 mod macros;
 
-use crate::{Apds9960Drv, Apds9960I2CPort};
+mod map;
+mod persistence;
+mod register;
+
+pub use self::{
+    map::{FieldInfo, RegisterInfo, REGISTER_MAP},
+    persistence::{AlsPersistence, ProximityPersistence},
+    register::{Access, ReadableRegister, Register, WritableRegister},
+};
+
+use crate::{Apds9960Drv, Apds9960Error, Apds9960I2CPort, Apds9960Owned, Apds9960Verified};
+use core::fmt;
 use drone_core::bitfield::Bitfield;
 use futures::prelude::*;
 
+/// Returns the bits of the register byte at `addr` that read back as written.
+///
+/// For registers with bitfields, reserved bits are excluded. Self-clearing
+/// `Gconf4::gfifo_clr` is excluded as well, and so is `Gconf4::gmode`, which
+/// the device clears when it leaves the gesture engine.
+pub(crate) fn retained_bits(addr: u8) -> u8 {
+    let mask = match RegisterInfo::by_addr(addr) {
+        Some(info) if !info.fields.is_empty() => {
+            info.fields.iter().fold(0, |mask, field| mask | field.mask()).to_le_bytes()[0]
+        }
+        _ => 0xFF,
+    };
+    if addr == Gconf4::ADDR { mask & !(1 << 2 | 1 << 0) } else { mask }
+}
+
 apds9960_reg! {
     /// Enable states and interrupts.
-    Enable u8 1 0x80 0x00 rw {
+    Enable "ENABLE" u8 1 0x80 0x00 rw {
         /// Reads contents of ENABLE register.
         fn load_enable;
         /// Writes `value` to ENABLE register.
@@ -30,7 +56,8 @@ apds9960_reg! {
 }
 
 apds9960_reg_raw! {
-    u8 1 0x81 rw {
+    /// ALS ADC integration time.
+    Atime "ATIME" u8 1 0x81 0xFF rw {
         /// Reads contents of ATIME register.
         fn load_atime;
         /// Writes `value` to ATIME register.
@@ -39,7 +66,8 @@ apds9960_reg_raw! {
 }
 
 apds9960_reg_raw! {
-    u8 1 0x83 rw {
+    /// Wait time (non-gesture).
+    Wtime "WTIME" u8 1 0x83 0xFF rw {
         /// Reads contents of WTIME register.
         fn load_wtime;
         /// Writes `value` to WTIME register.
@@ -48,7 +76,8 @@ apds9960_reg_raw! {
 }
 
 apds9960_reg_raw! {
-    u16 2 0x84 rw {
+    /// ALS low threshold.
+    Ailt "AILT" u16 2 0x84 0x00 rw {
         /// Reads contents of AILT register.
         fn load_ailt;
         /// Writes `value` to AILT register.
@@ -57,7 +86,8 @@ apds9960_reg_raw! {
 }
 
 apds9960_reg_raw! {
-    u16 2 0x86 rw {
+    /// ALS high threshold.
+    Aiht "AIHT" u16 2 0x86 0x00 rw {
         /// Reads contents of AIHT register.
         fn load_aiht;
         /// Writes `value` to AIHT register.
@@ -66,7 +96,8 @@ apds9960_reg_raw! {
 }
 
 apds9960_reg_raw! {
-    u8 1 0x89 rw {
+    /// Proximity low threshold.
+    Pilt "PILT" u8 1 0x89 0x00 rw {
         /// Reads contents of PILT register.
         fn load_pilt;
         /// Writes `value` to PILT register.
@@ -75,7 +106,8 @@ apds9960_reg_raw! {
 }
 
 apds9960_reg_raw! {
-    u8 1 0x8B rw {
+    /// Proximity high threshold.
+    Piht "PIHT" u8 1 0x8B 0x00 rw {
         /// Reads contents of PIHT register.
         fn load_piht;
         /// Writes `value` to PIHT register.
@@ -85,7 +117,7 @@ apds9960_reg_raw! {
 
 apds9960_reg! {
     /// Interrupt persistence filters (non-gesture.)
-    Pers u8 1 0x8C 0x00 rw {
+    Pers "PERS" u8 1 0x8C 0x00 rw {
         /// Reads contents of PERS register.
         fn load_pers;
         /// Writes `value` to PERS register.
@@ -102,7 +134,7 @@ apds9960_reg! {
 
 apds9960_reg! {
     /// Configuration register one.
-    Config1 u8 1 0x8D 0x60 rw {
+    Config1 "CONFIG1" u8 1 0x8D 0x60 rw {
         /// Reads contents of CONFIG1 register.
         fn load_config1;
         /// Writes `value` to CONFIG1 register.
@@ -118,7 +150,7 @@ apds9960_reg! {
 
 apds9960_reg! {
     /// Proximity pulse count and length.
-    Ppulse u8 1 0x8E 0x40 rw {
+    Ppulse "PPULSE" u8 1 0x8E 0x40 rw {
         /// Reads contents of PPULSE register.
         fn load_ppulse;
         /// Writes `value` to PPULSE register.
@@ -135,7 +167,7 @@ apds9960_reg! {
 
 apds9960_reg! {
     /// Gain control.
-    Control u8 1 0x8F 0x00 rw {
+    Control "CONTROL" u8 1 0x8F 0x00 rw {
         /// Reads contents of CONTROL register.
         fn load_control;
         /// Writes `value` to CONTROL register.
@@ -153,7 +185,7 @@ apds9960_reg! {
 
 apds9960_reg! {
     /// Configuration register two.
-    Config2 u8 1 0x90 0x01 rw {
+    Config2 "CONFIG2" u8 1 0x90 0x01 rw {
         /// Reads contents of CONFIG2 register.
         fn load_config2;
         /// Writes `value` to CONFIG2 register.
@@ -170,7 +202,8 @@ apds9960_reg! {
 }
 
 apds9960_reg_raw! {
-    u8 1 0x92 r {
+    /// Device ID.
+    Id "ID" u8 1 0x92 0xAB r {
         /// Reads contents of ID register.
         fn load_id;
     }
@@ -178,7 +211,7 @@ apds9960_reg_raw! {
 
 apds9960_reg! {
     /// Device status.
-    Status u8 1 0x93 0x00 r {
+    Status "STATUS" u8 1 0x93 0x00 r {
         /// Reads contents of STATUS register.
         fn load_status;
     }
@@ -195,42 +228,48 @@ apds9960_reg! {
 }
 
 apds9960_reg_raw! {
-    u16 2 0x94 r {
+    /// Clear channel data.
+    Cdata "CDATA" u16 2 0x94 0x00 r {
         /// Reads contents of CDATA register.
         fn load_cdata;
     }
 }
 
 apds9960_reg_raw! {
-    u16 2 0x96 r {
+    /// Red channel data.
+    Rdata "RDATA" u16 2 0x96 0x00 r {
         /// Reads contents of RDATA register.
         fn load_rdata;
     }
 }
 
 apds9960_reg_raw! {
-    u16 2 0x98 r {
+    /// Green channel data.
+    Gdata "GDATA" u16 2 0x98 0x00 r {
         /// Reads contents of GDATA register.
         fn load_gdata;
     }
 }
 
 apds9960_reg_raw! {
-    u16 2 0x9A r {
+    /// Blue channel data.
+    Bdata "BDATA" u16 2 0x9A 0x00 r {
         /// Reads contents of BDATA register.
         fn load_bdata;
     }
 }
 
 apds9960_reg_raw! {
-    u8 1 0x9C r {
-        /// Reads contents of BDATA register.
+    /// Proximity data.
+    Pdata "PDATA" u8 1 0x9C 0x00 r {
+        /// Reads contents of PDATA register.
         fn load_pdata;
     }
 }
 
 apds9960_reg_raw! {
-    u8 1 0x9D rw {
+    /// Proximity offset for UP and RIGHT photodiodes.
+    PoffsetUr "POFFSET_UR" u8 1 0x9D 0x00 rw {
         /// Reads contents of POFFSET_UR register.
         fn load_poffset_ur;
         /// Writes `value` to POFFSET_UR register.
@@ -239,7 +278,8 @@ apds9960_reg_raw! {
 }
 
 apds9960_reg_raw! {
-    u8 1 0x9E rw {
+    /// Proximity offset for DOWN and LEFT photodiodes.
+    PoffsetDl "POFFSET_DL" u8 1 0x9E 0x00 rw {
         /// Reads contents of POFFSET_DL register.
         fn load_poffset_dl;
         /// Writes `value` to POFFSET_DL register.
@@ -249,7 +289,7 @@ apds9960_reg_raw! {
 
 apds9960_reg! {
     /// Configuration register three.
-    Config3 u8 1 0x9F 0x00 rw {
+    Config3 "CONFIG3" u8 1 0x9F 0x00 rw {
         /// Reads contents of CONFIG3 register.
         fn load_config3;
         /// Writes `value` to CONFIG3 register.
@@ -269,7 +309,8 @@ apds9960_reg! {
 }
 
 apds9960_reg_raw! {
-    u8 1 0xA0 rw {
+    /// Gesture proximity enter threshold.
+    Gpenth "GPENTH" u8 1 0xA0 0x00 rw {
         /// Reads contents of GPENTH register.
         fn load_gpenth;
         /// Writes `value` to GPENTH register.
@@ -278,7 +319,8 @@ apds9960_reg_raw! {
 }
 
 apds9960_reg_raw! {
-    u8 1 0xA1 rw {
+    /// Gesture exit threshold.
+    Gexth "GEXTH" u8 1 0xA1 0x00 rw {
         /// Reads contents of GEXTH register.
         fn load_gexth;
         /// Writes `value` to GEXTH register.
@@ -288,7 +330,7 @@ apds9960_reg_raw! {
 
 apds9960_reg! {
     /// Gesture configuration one.
-    Gconf1 u8 1 0xA2 0x00 rw {
+    Gconf1 "GCONF1" u8 1 0xA2 0x00 rw {
         /// Reads contents of GCONF1 register.
         fn load_gconf1;
         /// Writes `value` to GCONF1 register.
@@ -306,7 +348,7 @@ apds9960_reg! {
 
 apds9960_reg! {
     /// Gesture configuration two.
-    Gconf2 u8 1 0xA3 0x00 rw {
+    Gconf2 "GCONF2" u8 1 0xA3 0x00 rw {
         /// Reads contents of GCONF2 register.
         fn load_gconf2;
         /// Writes `value` to GCONF2 register.
@@ -323,7 +365,8 @@ apds9960_reg! {
 }
 
 apds9960_reg_raw! {
-    u8 1 0xA4 rw {
+    /// Gesture UP offset.
+    GoffsetU "GOFFSET_U" u8 1 0xA4 0x00 rw {
         /// Reads contents of GOFFSET_U register.
         fn load_goffset_u;
         /// Writes `value` to GOFFSET_U register.
@@ -332,7 +375,8 @@ apds9960_reg_raw! {
 }
 
 apds9960_reg_raw! {
-    u8 1 0xA5 rw {
+    /// Gesture DOWN offset.
+    GoffsetD "GOFFSET_D" u8 1 0xA5 0x00 rw {
         /// Reads contents of GOFFSET_D register.
         fn load_goffset_d;
         /// Writes `value` to GOFFSET_D register.
@@ -341,7 +385,8 @@ apds9960_reg_raw! {
 }
 
 apds9960_reg_raw! {
-    u8 1 0xA7 rw {
+    /// Gesture LEFT offset.
+    GoffsetL "GOFFSET_L" u8 1 0xA7 0x00 rw {
         /// Reads contents of GOFFSET_L register.
         fn load_goffset_l;
         /// Writes `value` to GOFFSET_L register.
@@ -350,7 +395,8 @@ apds9960_reg_raw! {
 }
 
 apds9960_reg_raw! {
-    u8 1 0xA9 rw {
+    /// Gesture RIGHT offset.
+    GoffsetR "GOFFSET_R" u8 1 0xA9 0x00 rw {
         /// Reads contents of GOFFSET_R register.
         fn load_goffset_r;
         /// Writes `value` to GOFFSET_R register.
@@ -360,7 +406,7 @@ apds9960_reg_raw! {
 
 apds9960_reg! {
     /// Gesture pulse count and length.
-    Gpulse u8 1 0xA6 0x40 rw {
+    Gpulse "GPULSE" u8 1 0xA6 0x40 rw {
         /// Reads contents of GPULSE register.
         fn load_gpulse;
         /// Writes `value` to GPULSE register.
@@ -377,7 +423,7 @@ apds9960_reg! {
 
 apds9960_reg! {
     /// Gesture configuration three.
-    Gconf3 u8 1 0xAA 0x00 rw {
+    Gconf3 "GCONF3" u8 1 0xAA 0x00 rw {
         /// Reads contents of GCONF3 register.
         fn load_gconf3;
         /// Writes `value` to GCONF3 register.
@@ -393,7 +439,7 @@ apds9960_reg! {
 
 apds9960_reg! {
     /// Gesture configuration four.
-    Gconf4 u8 1 0xAB 0x00 rw {
+    Gconf4 "GCONF4" u8 1 0xAB 0x00 rw {
         /// Reads contents of GCONF4 register.
         fn load_gconf4;
         /// Writes `value` to GCONF4 register.
@@ -410,7 +456,8 @@ apds9960_reg! {
 }
 
 apds9960_reg_raw! {
-    u8 1 0xAE r {
+    /// Gesture FIFO level.
+    Gflvl "GFLVL" u8 1 0xAE 0x00 r {
         /// Reads contents of GFLVL register.
         fn load_gflvl;
     }
@@ -418,7 +465,7 @@ apds9960_reg_raw! {
 
 apds9960_reg! {
     /// Gesture status.
-    Gstatus u8 1 0xAF 0x00 r {
+    Gstatus "GSTATUS" u8 1 0xAF 0x00 r {
         /// Reads contents of GSTATUS register.
         fn load_gstatus;
     }
@@ -453,28 +500,32 @@ apds9960_reg_touch! {
 }
 
 apds9960_reg_raw! {
-    u8 1 0xFC r {
+    /// Gesture FIFO UP value.
+    GfifoU "GFIFO_U" u8 1 0xFC 0x00 r {
         /// Reads contents of GFIFO_U register.
         fn load_gfifo_u;
     }
 }
 
 apds9960_reg_raw! {
-    u8 1 0xFD r {
+    /// Gesture FIFO DOWN value.
+    GfifoD "GFIFO_D" u8 1 0xFD 0x00 r {
         /// Reads contents of GFIFO_D register.
         fn load_gfifo_d;
     }
 }
 
 apds9960_reg_raw! {
-    u8 1 0xFE r {
+    /// Gesture FIFO LEFT value.
+    GfifoL "GFIFO_L" u8 1 0xFE 0x00 r {
         /// Reads contents of GFIFO_L register.
         fn load_gfifo_l;
     }
 }
 
 apds9960_reg_raw! {
-    u8 1 0xFF r {
+    /// Gesture FIFO RIGHT value.
+    GfifoR "GFIFO_R" u8 1 0xFF 0x00 r {
         /// Reads contents of GFIFO_R register.
         fn load_gfifo_r;
     }
@@ -0,0 +1,233 @@
+use super::Pers;
+use core::{convert::TryFrom, time::Duration};
+
+/// ALS interrupt persistence, as encoded in [`Pers::apers`] field.
+///
+/// The encoding is non-linear: above 3 cycles the count grows in steps of 5.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum AlsPersistence {
+    /// Every ALS cycle generates an interrupt.
+    Every = 0,
+    /// Any value out of the threshold range.
+    Cycles1 = 1,
+    /// 2 consecutive values out of the threshold range.
+    Cycles2 = 2,
+    /// 3 consecutive values out of the threshold range.
+    Cycles3 = 3,
+    /// 5 consecutive values out of the threshold range.
+    Cycles5 = 4,
+    /// 10 consecutive values out of the threshold range.
+    Cycles10 = 5,
+    /// 15 consecutive values out of the threshold range.
+    Cycles15 = 6,
+    /// 20 consecutive values out of the threshold range.
+    Cycles20 = 7,
+    /// 25 consecutive values out of the threshold range.
+    Cycles25 = 8,
+    /// 30 consecutive values out of the threshold range.
+    Cycles30 = 9,
+    /// 35 consecutive values out of the threshold range.
+    Cycles35 = 10,
+    /// 40 consecutive values out of the threshold range.
+    Cycles40 = 11,
+    /// 45 consecutive values out of the threshold range.
+    Cycles45 = 12,
+    /// 50 consecutive values out of the threshold range.
+    Cycles50 = 13,
+    /// 55 consecutive values out of the threshold range.
+    Cycles55 = 14,
+    /// 60 consecutive values out of the threshold range.
+    Cycles60 = 15,
+}
+
+/// Proximity interrupt persistence, as encoded in [`Pers::ppers`] field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum ProximityPersistence {
+    /// Every proximity cycle generates an interrupt.
+    Every = 0,
+    /// Any value out of the threshold range.
+    Cycles1 = 1,
+    /// 2 consecutive values out of the threshold range.
+    Cycles2 = 2,
+    /// 3 consecutive values out of the threshold range.
+    Cycles3 = 3,
+    /// 4 consecutive values out of the threshold range.
+    Cycles4 = 4,
+    /// 5 consecutive values out of the threshold range.
+    Cycles5 = 5,
+    /// 6 consecutive values out of the threshold range.
+    Cycles6 = 6,
+    /// 7 consecutive values out of the threshold range.
+    Cycles7 = 7,
+    /// 8 consecutive values out of the threshold range.
+    Cycles8 = 8,
+    /// 9 consecutive values out of the threshold range.
+    Cycles9 = 9,
+    /// 10 consecutive values out of the threshold range.
+    Cycles10 = 10,
+    /// 11 consecutive values out of the threshold range.
+    Cycles11 = 11,
+    /// 12 consecutive values out of the threshold range.
+    Cycles12 = 12,
+    /// 13 consecutive values out of the threshold range.
+    Cycles13 = 13,
+    /// 14 consecutive values out of the threshold range.
+    Cycles14 = 14,
+    /// 15 consecutive values out of the threshold range.
+    Cycles15 = 15,
+}
+
+const ALS_PERSISTENCE: [AlsPersistence; 16] = [
+    AlsPersistence::Every,
+    AlsPersistence::Cycles1,
+    AlsPersistence::Cycles2,
+    AlsPersistence::Cycles3,
+    AlsPersistence::Cycles5,
+    AlsPersistence::Cycles10,
+    AlsPersistence::Cycles15,
+    AlsPersistence::Cycles20,
+    AlsPersistence::Cycles25,
+    AlsPersistence::Cycles30,
+    AlsPersistence::Cycles35,
+    AlsPersistence::Cycles40,
+    AlsPersistence::Cycles45,
+    AlsPersistence::Cycles50,
+    AlsPersistence::Cycles55,
+    AlsPersistence::Cycles60,
+];
+
+const PROXIMITY_PERSISTENCE: [ProximityPersistence; 16] = [
+    ProximityPersistence::Every,
+    ProximityPersistence::Cycles1,
+    ProximityPersistence::Cycles2,
+    ProximityPersistence::Cycles3,
+    ProximityPersistence::Cycles4,
+    ProximityPersistence::Cycles5,
+    ProximityPersistence::Cycles6,
+    ProximityPersistence::Cycles7,
+    ProximityPersistence::Cycles8,
+    ProximityPersistence::Cycles9,
+    ProximityPersistence::Cycles10,
+    ProximityPersistence::Cycles11,
+    ProximityPersistence::Cycles12,
+    ProximityPersistence::Cycles13,
+    ProximityPersistence::Cycles14,
+    ProximityPersistence::Cycles15,
+];
+
+impl AlsPersistence {
+    /// Decodes a 4-bit APERS field value.
+    #[must_use]
+    pub fn from_bits(bits: u8) -> Self {
+        ALS_PERSISTENCE[usize::from(bits & 0xF)]
+    }
+
+    /// Returns the 4-bit APERS field value.
+    #[must_use]
+    pub fn bits(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns the number of consecutive out-of-range cycles required to
+    /// generate an interrupt. Returns 0 for [`AlsPersistence::Every`].
+    #[must_use]
+    pub fn cycles(self) -> u8 {
+        match self.bits() {
+            bits @ 0..=3 => bits,
+            bits => (bits - 3) * 5,
+        }
+    }
+
+    /// Returns the encoding closest to `cycles` consecutive out-of-range
+    /// cycles.
+    ///
+    /// Never returns [`AlsPersistence::Every`], as it disables threshold
+    /// comparison. Ties are resolved towards the shorter count.
+    #[must_use]
+    pub fn nearest(cycles: u32) -> Self {
+        nearest(&ALS_PERSISTENCE, cycles, Self::cycles)
+    }
+
+    /// Returns the encoding closest to debounce `duration`, given the
+    /// current ALS `cycle` time.
+    #[must_use]
+    pub fn for_duration(duration: Duration, cycle: Duration) -> Self {
+        Self::nearest(duration_cycles(duration, cycle))
+    }
+}
+
+impl ProximityPersistence {
+    /// Decodes a 4-bit PPERS field value.
+    #[must_use]
+    pub fn from_bits(bits: u8) -> Self {
+        PROXIMITY_PERSISTENCE[usize::from(bits & 0xF)]
+    }
+
+    /// Returns the 4-bit PPERS field value.
+    #[must_use]
+    pub fn bits(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns the number of consecutive out-of-range cycles required to
+    /// generate an interrupt. Returns 0 for [`ProximityPersistence::Every`].
+    #[must_use]
+    pub fn cycles(self) -> u8 {
+        self.bits()
+    }
+
+    /// Returns the encoding closest to `cycles` consecutive out-of-range
+    /// cycles.
+    ///
+    /// Never returns [`ProximityPersistence::Every`], as it disables threshold
+    /// comparison.
+    #[must_use]
+    pub fn nearest(cycles: u32) -> Self {
+        nearest(&PROXIMITY_PERSISTENCE, cycles, Self::cycles)
+    }
+
+    /// Returns the encoding closest to debounce `duration`, given the
+    /// current proximity `cycle` time.
+    #[must_use]
+    pub fn for_duration(duration: Duration, cycle: Duration) -> Self {
+        Self::nearest(duration_cycles(duration, cycle))
+    }
+}
+
+impl Pers {
+    /// Returns ALS interrupt persistence.
+    #[must_use]
+    pub fn als_persistence(self) -> AlsPersistence {
+        AlsPersistence::from_bits(self.apers())
+    }
+
+    /// Sets ALS interrupt persistence.
+    pub fn write_als_persistence(&mut self, persistence: AlsPersistence) -> &mut Self {
+        self.write_apers(persistence.bits())
+    }
+
+    /// Returns proximity interrupt persistence.
+    #[must_use]
+    pub fn proximity_persistence(self) -> ProximityPersistence {
+        ProximityPersistence::from_bits(self.ppers())
+    }
+
+    /// Sets proximity interrupt persistence.
+    pub fn write_proximity_persistence(&mut self, persistence: ProximityPersistence) -> &mut Self {
+        self.write_ppers(persistence.bits())
+    }
+}
+
+fn nearest<T: Copy>(table: &[T], cycles: u32, f: impl Fn(T) -> u8) -> T {
+    table[1..].iter().copied().min_by_key(|&p| (i64::from(f(p)) - i64::from(cycles)).abs()).unwrap()
+}
+
+fn duration_cycles(duration: Duration, cycle: Duration) -> u32 {
+    let cycle = cycle.as_nanos().max(1);
+    let cycles = (duration.as_nanos() + cycle / 2) / cycle;
+    u32::try_from(cycles).unwrap_or(u32::MAX)
+}
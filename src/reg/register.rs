@@ -0,0 +1,65 @@
+use super::{FieldInfo, RegisterInfo};
+
+/// Register access mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// Read-only register.
+    Read,
+    /// Write-only register.
+    Write,
+    /// Read-write register.
+    ReadWrite,
+}
+
+/// APDS-9960 register.
+///
+/// Implemented by every register type in this module. Together with
+/// [`ReadableRegister`] and [`WritableRegister`] it allows accessing registers
+/// generically with [`Apds9960Drv::load`](crate::Apds9960Drv::load),
+/// [`Apds9960Drv::store`](crate::Apds9960Drv::store) and
+/// [`Apds9960Drv::modify`](crate::Apds9960Drv::modify).
+pub trait Register: Sized + Copy + Default {
+    /// Register name as in the datasheet.
+    const NAME: &'static str;
+
+    /// Register address.
+    const ADDR: u8;
+
+    /// Register size in bytes.
+    const SIZE: usize;
+
+    /// Register reset value.
+    const RESET: u16;
+
+    /// Register access mode.
+    const ACCESS: Access;
+
+    /// Register description.
+    const DESCRIPTION: &'static str;
+
+    /// Register bitfields.
+    const FIELDS: &'static [FieldInfo];
+
+    /// Register description for [`REGISTER_MAP`](super::REGISTER_MAP).
+    const INFO: RegisterInfo = RegisterInfo {
+        name: Self::NAME,
+        addr: Self::ADDR,
+        size: Self::SIZE,
+        access: Self::ACCESS,
+        reset: Self::RESET,
+        description: Self::DESCRIPTION,
+        fields: Self::FIELDS,
+    };
+
+    /// Creates a register value from raw bits.
+    fn from_raw(raw: u16) -> Self;
+
+    /// Returns raw bits of the register value.
+    fn into_raw(self) -> u16;
+}
+
+/// Register that can be read.
+pub trait ReadableRegister: Register {}
+
+/// Register that can be written.
+pub trait WritableRegister: Register {}
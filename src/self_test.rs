@@ -0,0 +1,219 @@
+use crate::{
+    measure::Deadline,
+    reg::{Atime, Control, Enable, Gconf4, Piht, Pilt, Register, Wtime},
+    Apds9960DelayPort, Apds9960Drv, Apds9960Error, Apds9960I2CPort, RegisterBatch, Rgbc,
+};
+use core::time::Duration;
+
+/// Expected contents of ID register.
+const DEVICE_ID: u8 = 0xAB;
+
+/// Registers used for the read-back check.
+const READBACK_REGS: [u8; 4] = [Atime::ADDR, Wtime::ADDR, Pilt::ADDR, Piht::ADDR];
+
+/// Patterns written during the read-back check.
+const READBACK_PATTERNS: [u8; 2] = [0x55, 0xAA];
+
+/// ATIME used for the ALS check, 100 ms.
+const ALS_ATIME: u8 = 0xDB;
+
+/// Clear channel count at which the ALS check integration saturates
+/// digitally, `min(1025 * (256 - ATIME), 65535)`.
+#[allow(clippy::cast_possible_truncation)]
+const ALS_SATURATION: u16 = {
+    let counts = 1025 * (256 - ALS_ATIME as u32);
+    if counts < 0xFFFF { counts as u16 } else { 0xFFFF }
+};
+
+/// Maximum LDRIVE value, which selects the minimum LED current.
+const LDRIVE_MIN: u8 = 3;
+
+/// Minimum LDRIVE value, which selects the maximum LED current.
+const LDRIVE_MAX: u8 = 0;
+
+/// Interval between GFLVL checks.
+const GESTURE_POLL: Duration = Duration::from_millis(10);
+
+/// Outcome of a single self-test check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelfTestItem<T> {
+    /// The check passed.
+    pub passed: bool,
+    /// The value measured by the check.
+    pub value: T,
+}
+
+/// Result of [`Apds9960Drv::self_test`].
+///
+/// Every check reports the values it measured, so limits can be tightened
+/// for a particular test fixture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelfTestReport {
+    /// Contents of ID register.
+    pub id: SelfTestItem<u8>,
+    /// Number of registers that failed the read-back check.
+    pub readback: SelfTestItem<u8>,
+    /// Proximity with the minimum and the maximum LED current.
+    pub led: SelfTestItem<[u8; 2]>,
+    /// `Status::pgsat` was set during either LED check measurement.
+    pub led_saturated: bool,
+    /// ALS measurement, checked against the dark count limit.
+    pub als: SelfTestItem<Rgbc>,
+    /// `Status::cpsat` was set during the ALS measurement.
+    pub als_saturated: bool,
+    /// Gesture FIFO level reached during the check.
+    pub gesture: SelfTestItem<u8>,
+}
+
+impl SelfTestReport {
+    /// Returns `true` if all checks passed.
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.id.passed
+            && self.readback.passed
+            && self.led.passed
+            && self.als.passed
+            && self.gesture.passed
+    }
+}
+
+impl<A> Apds9960Drv<A> {
+    /// Runs a factory self-test.
+    ///
+    /// Performs the following checks:
+    ///
+    /// 1. ID register contains the APDS-9960 device ID.
+    /// 2. Test patterns written to ATIME, WTIME, PILT and PIHT are read back
+    ///    unchanged.
+    /// 3. Proximity measured with the maximum LED current is greater than with
+    ///    the minimum LED current, which proves the IR LED works, and neither
+    ///    measurement sets `Status::pgsat`. Requires a reflective target
+    ///    above the sensor.
+    /// 4. A 100 ms ALS measurement completes without `Status::cpsat`, the
+    ///    clear channel is not digitally saturated, and the clear count
+    ///    doesn't exceed `max_dark`. In a fixture that covers the sensor, this
+    ///    checks the dark counts; pass `u16::MAX` to check only the
+    ///    saturation.
+    ///
+    /// The saturation flags are reported separately in
+    /// [`SelfTestReport::led_saturated`] and
+    /// [`SelfTestReport::als_saturated`].
+    /// 5. The gesture engine forced with `Gconf4::gmode` fills the FIFO, and
+    ///    `Gconf4::gfifo_clr` empties it.
+    ///
+    /// Every measurement waits at most `timeout`; a timed out check is
+    /// reported as failed. The device is powered off during the test, and
+    /// the registers touched by the test, including ENABLE, are restored
+    /// afterwards.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated as
+    /// [`Apds9960Error::Port`]. The registers are not restored in this case.
+    pub async fn self_test<P, D>(
+        &mut self,
+        i2c: &mut P,
+        delay: &mut D,
+        timeout: Duration,
+        max_dark: u16,
+    ) -> Result<SelfTestReport, Apds9960Error<P::Error>>
+    where
+        P: Apds9960I2CPort<A>,
+        D: Apds9960DelayPort<A>,
+    {
+        let mut report = SelfTestReport::default();
+        let saved = self.dump_registers(i2c, false).await.map_err(Apds9960Error::Port)?;
+        self.store(i2c, Enable::default()).await.map_err(Apds9960Error::Port)?;
+
+        let id = self.load_id(i2c).await.map_err(Apds9960Error::Port)?;
+        report.id = SelfTestItem { passed: id == DEVICE_ID, value: id };
+
+        let mut errors = 0;
+        for &reg in &READBACK_REGS {
+            let mut failed = false;
+            for &pattern in &READBACK_PATTERNS {
+                self.store_reg(i2c, pattern.into(), reg, 1).await.map_err(Apds9960Error::Port)?;
+                let value = self.load_reg(i2c, reg, 1).await.map_err(Apds9960Error::Port)?;
+                failed |= value != u16::from(pattern);
+            }
+            errors += u8::from(failed);
+        }
+        report.readback = SelfTestItem { passed: errors == 0, value: errors };
+
+        self.store(i2c, Atime(ALS_ATIME)).await.map_err(Apds9960Error::Port)?;
+        self.store(i2c, Wtime(0xFF)).await.map_err(Apds9960Error::Port)?;
+        self.store(i2c, *Control::default().write_ldrive(LDRIVE_MIN))
+            .await
+            .map_err(Apds9960Error::Port)?;
+        let min = timed_out(self.measure_proximity_status(i2c, delay, timeout).await)?;
+        self.store(i2c, *Control::default().write_ldrive(LDRIVE_MAX))
+            .await
+            .map_err(Apds9960Error::Port)?;
+        let max = timed_out(self.measure_proximity_status(i2c, delay, timeout).await)?;
+        report.led_saturated = [min, max].iter().flatten().any(|(_, status)| status.pgsat());
+        report.led = match (min, max) {
+            (Some((min, _)), Some((max, _))) => {
+                SelfTestItem { passed: max > min && !report.led_saturated, value: [min, max] }
+            }
+            _ => SelfTestItem {
+                passed: false,
+                value: [min.map_or(0, |(min, _)| min), max.map_or(0, |(max, _)| max)],
+            },
+        };
+
+        let als = timed_out(self.measure_rgbc_status(i2c, delay, timeout).await)?;
+        report.als = match als {
+            Some((als, status)) => {
+                report.als_saturated = status.cpsat();
+                let saturated = report.als_saturated || als.clear >= ALS_SATURATION;
+                SelfTestItem { passed: !saturated && als.clear <= max_dark, value: als }
+            }
+            None => SelfTestItem { passed: false, value: Rgbc::default() },
+        };
+
+        self.store(i2c, *Gconf4::default().set_gfifo_clr()).await.map_err(Apds9960Error::Port)?;
+        self.store(i2c, *Gconf4::default().set_gmode()).await.map_err(Apds9960Error::Port)?;
+        self.store(i2c, *Enable::default().set_pon().set_pen().set_gen())
+            .await
+            .map_err(Apds9960Error::Port)?;
+        let mut level;
+        let mut deadline = Deadline::new(delay, timeout);
+        loop {
+            delay.delay(GESTURE_POLL).await;
+            deadline.advance(GESTURE_POLL);
+            level = self.load_gflvl(i2c).await.map_err(Apds9960Error::Port)?;
+            if level > 0 || deadline.expired(delay) {
+                break;
+            }
+        }
+        self.store(i2c, Enable::default()).await.map_err(Apds9960Error::Port)?;
+        self.store(i2c, *Gconf4::default().set_gfifo_clr()).await.map_err(Apds9960Error::Port)?;
+        let cleared = self.load_gflvl(i2c).await.map_err(Apds9960Error::Port)? == 0;
+        report.gesture = SelfTestItem { passed: level > 0 && cleared, value: level };
+
+        let mut batch = RegisterBatch::new();
+        batch
+            .push(Atime(saved.atime()))
+            .push(Wtime(saved.wtime()))
+            .push(Pilt(saved.pilt()))
+            .push(Piht(saved.piht()))
+            .push(saved.control())
+            .push(saved.gconf4());
+        self.store_batch(i2c, &batch).await.map_err(Apds9960Error::Port)?;
+        self.store(i2c, saved.enable()).await.map_err(Apds9960Error::Port)?;
+        Ok(report)
+    }
+}
+
+/// Turns [`Apds9960Error::Timeout`] into `Ok(None)`.
+fn timed_out<T, E>(result: Result<T, Apds9960Error<E>>) -> Result<Option<T>, Apds9960Error<E>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(Apds9960Error::Timeout) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
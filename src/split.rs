@@ -0,0 +1,278 @@
+use crate::{
+    reg::{Enable, Gconf4},
+    sync::Mutex,
+    Apds9960Drv, Apds9960I2CPort, Rgbc,
+};
+use alloc::sync::Arc;
+
+/// The driver and the bus shared by the parts.
+struct Shared<A, P> {
+    drv: Apds9960Drv<A>,
+    i2c: P,
+    als: bool,
+    proximity: bool,
+    gesture: bool,
+}
+
+/// ALS part of a split driver.
+pub struct AlsPart<A, P> {
+    shared: Arc<Mutex<Shared<A, P>>>,
+}
+
+/// Proximity part of a split driver.
+pub struct ProximityPart<A, P> {
+    shared: Arc<Mutex<Shared<A, P>>>,
+}
+
+/// Gesture part of a split driver.
+pub struct GesturePart<A, P> {
+    shared: Arc<Mutex<Shared<A, P>>>,
+}
+
+impl<A> Apds9960Drv<A> {
+    /// Splits the driver into independently owned engine parts.
+    ///
+    /// The parts share the driver and `i2c` through an async mutex, so they
+    /// can be moved to different tasks. Each part enables and disables only
+    /// its own engine: the device is powered on while at least one engine is
+    /// enabled, `Enable::pen` is kept while either the proximity or the
+    /// gesture engine needs it, and the other ENABLE bits are preserved.
+    ///
+    /// All engines are assumed to be disabled at the time of splitting.
+    pub fn split<P: Apds9960I2CPort<A>>(
+        self,
+        i2c: P,
+    ) -> (AlsPart<A, P>, ProximityPart<A, P>, GesturePart<A, P>) {
+        let shared = Arc::new(Mutex::new(Shared {
+            drv: self,
+            i2c,
+            als: false,
+            proximity: false,
+            gesture: false,
+        }));
+        (
+            AlsPart { shared: Arc::clone(&shared) },
+            ProximityPart { shared: Arc::clone(&shared) },
+            GesturePart { shared },
+        )
+    }
+
+    /// Joins the parts back into the driver and the bus.
+    ///
+    /// # Errors
+    ///
+    /// Returns the parts back if they come from different
+    /// [`split`](Apds9960Drv::split) calls.
+    #[allow(clippy::type_complexity)]
+    pub fn unsplit<P>(
+        als: AlsPart<A, P>,
+        proximity: ProximityPart<A, P>,
+        gesture: GesturePart<A, P>,
+    ) -> Result<(Self, P), (AlsPart<A, P>, ProximityPart<A, P>, GesturePart<A, P>)> {
+        if !Arc::ptr_eq(&als.shared, &proximity.shared)
+            || !Arc::ptr_eq(&als.shared, &gesture.shared)
+        {
+            return Err((als, proximity, gesture));
+        }
+        drop(proximity);
+        drop(gesture);
+        match Arc::try_unwrap(als.shared) {
+            Ok(shared) => {
+                let Shared { drv, i2c, .. } = shared.into_inner();
+                Ok((drv, i2c))
+            }
+            Err(_) => unreachable!(),
+        }
+    }
+}
+
+impl<A, P: Apds9960I2CPort<A>> Shared<A, P> {
+    /// Writes ENABLE bits derived from the engine flags, preserving the
+    /// others.
+    async fn update_enable(&mut self) -> Result<(), P::Error> {
+        let Self { drv, i2c, als, proximity, gesture } = self;
+        let (als, proximity, gesture) = (*als, *proximity, *gesture);
+        drv.modify::<Enable, _, _>(i2c, |r| {
+            set(r, Enable::set_pon, Enable::clear_pon, als || proximity || gesture);
+            set(r, Enable::set_aen, Enable::clear_aen, als);
+            set(r, Enable::set_pen, Enable::clear_pen, proximity || gesture);
+            set(r, Enable::set_gen, Enable::clear_gen, gesture)
+        })
+        .await
+    }
+}
+
+fn set(
+    r: &mut Enable,
+    on: fn(&mut Enable) -> &mut Enable,
+    off: fn(&mut Enable) -> &mut Enable,
+    value: bool,
+) -> &mut Enable {
+    if value { on(r) } else { off(r) }
+}
+
+impl<A, P: Apds9960I2CPort<A>> AlsPart<A, P> {
+    /// Enables the ALS engine.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn enable(&mut self) -> Result<(), P::Error> {
+        let mut shared = self.shared.lock().await;
+        shared.als = true;
+        shared.update_enable().await
+    }
+
+    /// Disables the ALS engine.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn disable(&mut self) -> Result<(), P::Error> {
+        let mut shared = self.shared.lock().await;
+        shared.als = false;
+        shared.update_enable().await
+    }
+
+    /// Returns `true` if `Status::avalid` is set.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn is_valid(&mut self) -> Result<bool, P::Error> {
+        let shared = &mut *self.shared.lock().await;
+        Ok(shared.drv.load_status(&mut shared.i2c).await?.avalid())
+    }
+
+    /// Reads RGBC data.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn load_rgbc(&mut self) -> Result<Rgbc, P::Error> {
+        let shared = &mut *self.shared.lock().await;
+        shared.drv.load_rgbc(&mut shared.i2c).await
+    }
+
+    /// Clears the ALS interrupt.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn clear_interrupt(&mut self) -> Result<(), P::Error> {
+        let shared = &mut *self.shared.lock().await;
+        shared.drv.touch_ciclear(&mut shared.i2c).await
+    }
+}
+
+impl<A, P: Apds9960I2CPort<A>> ProximityPart<A, P> {
+    /// Enables the proximity engine.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn enable(&mut self) -> Result<(), P::Error> {
+        let mut shared = self.shared.lock().await;
+        shared.proximity = true;
+        shared.update_enable().await
+    }
+
+    /// Disables the proximity engine.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn disable(&mut self) -> Result<(), P::Error> {
+        let mut shared = self.shared.lock().await;
+        shared.proximity = false;
+        shared.update_enable().await
+    }
+
+    /// Returns `true` if `Status::pvalid` is set.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn is_valid(&mut self) -> Result<bool, P::Error> {
+        let shared = &mut *self.shared.lock().await;
+        Ok(shared.drv.load_status(&mut shared.i2c).await?.pvalid())
+    }
+
+    /// Reads proximity data.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn load_pdata(&mut self) -> Result<u8, P::Error> {
+        let shared = &mut *self.shared.lock().await;
+        shared.drv.load_pdata(&mut shared.i2c).await
+    }
+
+    /// Clears the proximity interrupt.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn clear_interrupt(&mut self) -> Result<(), P::Error> {
+        let shared = &mut *self.shared.lock().await;
+        shared.drv.touch_piclear(&mut shared.i2c).await
+    }
+}
+
+impl<A, P: Apds9960I2CPort<A>> GesturePart<A, P> {
+    /// Enables the gesture engine in forced gesture mode.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn enable(&mut self) -> Result<(), P::Error> {
+        let shared = &mut *self.shared.lock().await;
+        shared.drv.modify::<Gconf4, _, _>(&mut shared.i2c, |r| r.set_gmode()).await?;
+        shared.gesture = true;
+        shared.update_enable().await
+    }
+
+    /// Disables the gesture engine and leaves gesture mode.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn disable(&mut self) -> Result<(), P::Error> {
+        let shared = &mut *self.shared.lock().await;
+        shared.gesture = false;
+        shared.update_enable().await?;
+        shared.drv.modify::<Gconf4, _, _>(&mut shared.i2c, |r| r.clear_gmode()).await
+    }
+
+    /// Reads the number of datasets in the gesture FIFO.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn load_level(&mut self) -> Result<u8, P::Error> {
+        let shared = &mut *self.shared.lock().await;
+        shared.drv.load_gflvl(&mut shared.i2c).await
+    }
+
+    /// Reads all datasets from the gesture FIFO.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn drain_fifo(&mut self) -> Result<Vec<[u8; 4]>, P::Error> {
+        let shared = &mut *self.shared.lock().await;
+        let level = shared.drv.load_gflvl(&mut shared.i2c).await?;
+        let data = shared.drv.drain_fifo(&mut shared.i2c, level).await?;
+        Ok(data.chunks_exact(4).map(|chunk| [chunk[0], chunk[1], chunk[2], chunk[3]]).collect())
+    }
+
+    /// Clears the gesture FIFO and the gesture interrupt.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn clear_fifo(&mut self) -> Result<(), P::Error> {
+        let shared = &mut *self.shared.lock().await;
+        shared.drv.modify::<Gconf4, _, _>(&mut shared.i2c, |r| r.set_gfifo_clr()).await
+    }
+}
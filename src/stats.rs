@@ -0,0 +1,136 @@
+use crate::Apds9960Drv;
+
+/// STATUS register address.
+const STATUS: u8 = 0x93;
+
+/// GSTATUS register address.
+const GSTATUS: u8 = 0xAF;
+
+/// `GFIFO_U` register address.
+const GFIFO: u8 = 0xFC;
+
+/// Bus counters collected by [`Apds9960Drv`].
+///
+/// Collection is enabled with
+/// [`enable_stats`](Apds9960Drv::enable_stats).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BusStats {
+    /// Number of I²C transactions.
+    pub transactions: u32,
+    /// Number of bytes received.
+    pub bytes_read: u32,
+    /// Number of bytes sent, including register addresses.
+    pub bytes_written: u32,
+    /// Number of failed transactions.
+    pub errors: u32,
+    /// Number of transaction retries reported by the port, see
+    /// [`Retry`](crate::adapters::Retry).
+    pub retries: u32,
+    /// Number of reads that found `Gstatus::gfov` set.
+    pub fifo_overflows: u32,
+    /// Number of reads that found `Status::pgsat` set.
+    pub proximity_saturations: u32,
+    /// Number of reads that found `Status::cpsat` set.
+    pub clear_saturations: u32,
+    register_errors: Vec<(u8, u32)>,
+}
+
+impl BusStats {
+    /// Returns the number of failed transactions starting at `reg` register.
+    #[must_use]
+    pub fn register_errors(&self, reg: u8) -> u32 {
+        self.register_errors
+            .binary_search_by_key(&reg, |&(reg, _)| reg)
+            .map_or(0, |i| self.register_errors[i].1)
+    }
+
+    /// Returns the numbers of failed transactions per register address, for
+    /// registers with at least one failure, ordered by address.
+    #[must_use]
+    pub fn errors_by_register(&self) -> &[(u8, u32)] {
+        &self.register_errors
+    }
+
+    fn record_error(&mut self, reg: u8) {
+        self.errors += 1;
+        match self.register_errors.binary_search_by_key(&reg, |&(reg, _)| reg) {
+            Ok(i) => self.register_errors[i].1 += 1,
+            Err(i) => self.register_errors.insert(i, (reg, 1)),
+        }
+    }
+}
+
+impl<A> Apds9960Drv<A> {
+    /// Starts collecting bus counters.
+    ///
+    /// Does nothing if the collection is already enabled.
+    pub fn enable_stats(&mut self) {
+        self.stats.get_or_insert_with(BusStats::default);
+    }
+
+    /// Stops collecting bus counters and drops the collected values.
+    pub fn disable_stats(&mut self) {
+        self.stats = None;
+    }
+
+    /// Returns a snapshot of the collected counters, or `None` if the
+    /// collection is disabled.
+    #[must_use]
+    pub fn stats(&self) -> Option<BusStats> {
+        self.stats.clone()
+    }
+
+    /// Resets the collected counters to zero.
+    pub fn reset_stats(&mut self) {
+        if let Some(stats) = &mut self.stats {
+            *stats = BusStats::default();
+        }
+    }
+
+    /// Records a read of `size` bytes from `reg` register, repeated `retries`
+    /// times by the port. On success, the bytes are expected at the start of
+    /// the internal buffer.
+    pub(crate) fn record_load(&mut self, reg: u8, size: usize, ok: bool, retries: u32) {
+        let Self { stats, buf, .. } = self;
+        let stats = match stats {
+            Some(stats) => stats,
+            None => return,
+        };
+        stats.transactions += 1;
+        stats.retries += retries;
+        if !ok {
+            stats.record_error(reg);
+            return;
+        }
+        stats.bytes_written += 1;
+        #[allow(clippy::cast_possible_truncation)]
+        let read = size as u32;
+        stats.bytes_read += read;
+        for (addr, &byte) in (reg..GFIFO).zip(&buf[..size]) {
+            match addr {
+                STATUS => {
+                    stats.proximity_saturations += u32::from(byte >> 6 & 1);
+                    stats.clear_saturations += u32::from(byte >> 7 & 1);
+                }
+                GSTATUS => stats.fifo_overflows += u32::from(byte >> 1 & 1),
+                _ => {}
+            }
+        }
+    }
+
+    /// Records a write of `count` bytes, including the address, to `reg`
+    /// register, repeated `retries` times by the port.
+    pub(crate) fn record_store(&mut self, reg: u8, count: usize, ok: bool, retries: u32) {
+        if let Some(stats) = &mut self.stats {
+            stats.transactions += 1;
+            stats.retries += retries;
+            if ok {
+                #[allow(clippy::cast_possible_truncation)]
+                let count = count as u32;
+                stats.bytes_written += count;
+            } else {
+                stats.record_error(reg);
+            }
+        }
+    }
+}
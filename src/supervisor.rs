@@ -0,0 +1,191 @@
+use crate::{
+    reg::{retained_bits, Enable, Register, Status, WritableRegister},
+    Apds9960Drv, Apds9960I2CPort, RegisterBatch,
+};
+
+/// A device reset detected by [`Supervisor`].
+///
+/// Describes the first register that didn't match the applied configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceReset {
+    /// Register address.
+    pub reg: u8,
+    /// Applied register value.
+    pub expected: u8,
+    /// Register value read from the device.
+    pub found: u8,
+}
+
+/// Brown-out and reset supervision around [`Apds9960Drv`].
+///
+/// After a brown-out the device comes back with all registers at their reset
+/// values, including ENABLE, and silently stops measuring. The supervisor
+/// keeps the last applied configuration and compares it with the device. On
+/// a mismatch it writes the whole configuration again and reports a
+/// [`DeviceReset`].
+///
+/// ```no_run
+/// # use smartoris_apds9960::{reg::{Atime, Enable}, Apds9960Drv, Apds9960I2CPort,
+/// #     RegisterBatch, Supervisor};
+/// # async fn f<P: Apds9960I2CPort<()>>(i2c: &mut P) -> Result<(), P::Error> {
+/// let mut config = RegisterBatch::new();
+/// config.push(Atime(0xDB)).push(*Enable::default().set_pon().set_aen());
+/// let mut supervisor = Supervisor::new(Apds9960Drv::<()>::init(), config);
+/// supervisor.apply(i2c).await?;
+/// loop {
+///     let (status, reset) = supervisor.load_status(i2c).await?;
+///     if let Some(reset) = reset {
+///         // Report the event.
+///     }
+/// }
+/// # }
+/// ```
+///
+/// Configuration registers must be changed through
+/// [`store`](Supervisor::store), otherwise the change is detected as a reset
+/// and reverted.
+pub struct Supervisor<A> {
+    drv: Apds9960Drv<A>,
+    config: RegisterBatch,
+    resets: u32,
+}
+
+impl<A> Supervisor<A> {
+    /// Wraps `drv` with `config` as the configuration to maintain.
+    ///
+    /// The device is not accessed until [`apply`](Supervisor::apply).
+    #[must_use]
+    pub fn new(drv: Apds9960Drv<A>, config: RegisterBatch) -> Self {
+        Self { drv, config, resets: 0 }
+    }
+
+    /// Returns the inner driver for data registers access.
+    pub fn drv(&mut self) -> &mut Apds9960Drv<A> {
+        &mut self.drv
+    }
+
+    /// Releases the inner driver.
+    #[must_use]
+    pub fn release(self) -> Apds9960Drv<A> {
+        self.drv
+    }
+
+    /// Returns the maintained configuration.
+    #[must_use]
+    pub fn config(&self) -> &RegisterBatch {
+        &self.config
+    }
+
+    /// Returns the number of resets detected so far.
+    #[must_use]
+    pub fn resets(&self) -> u32 {
+        self.resets
+    }
+
+    /// Writes the whole configuration to the device.
+    ///
+    /// ENABLE register is written last, so the engines start with the rest
+    /// of the configuration in place.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn apply<P: Apds9960I2CPort<A>>(&mut self, i2c: &mut P) -> Result<(), P::Error> {
+        for run in self.config.runs() {
+            let run = if run[0].0 == Enable::ADDR { &run[1..] } else { run };
+            if let Some(&(addr, _)) = run.first() {
+                self.drv.store_burst(i2c, addr, run.iter().map(|&(_, byte)| byte)).await?;
+            }
+        }
+        if let Some(enable) = self.config.get(Enable::ADDR) {
+            self.drv.store_enable_val(i2c, Enable::from(enable)).await?;
+        }
+        Ok(())
+    }
+
+    /// Writes `value` to `R` register and records it in the configuration.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn store<R: WritableRegister, P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+        value: R,
+    ) -> Result<(), P::Error> {
+        self.drv.store(i2c, value).await?;
+        self.config.push(value);
+        Ok(())
+    }
+
+    /// Compares every configured register with the device, and re-applies
+    /// the configuration on a mismatch.
+    ///
+    /// Takes one I²C transaction per run of consecutive addresses in the
+    /// configuration.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn check<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+    ) -> Result<Option<DeviceReset>, P::Error> {
+        let mut reset = None;
+        for run in self.config.runs() {
+            let found = self.drv.load_burst(i2c, run[0].0, run.len()).await?;
+            reset = run
+                .iter()
+                .zip(found)
+                .find_map(|(&(reg, expected), &found)| mismatch(reg, expected, found));
+            if reset.is_some() {
+                break;
+            }
+        }
+        self.restore(i2c, reset).await
+    }
+
+    /// Reads STATUS register and checks ENABLE register against the
+    /// configuration, re-applying the configuration on a mismatch.
+    ///
+    /// This is a cheaper alternative to [`check`](Supervisor::check) for
+    /// every status poll. It detects a reset only if ENABLE is part of the
+    /// configuration and differs from its reset value.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn load_status<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+    ) -> Result<(Status, Option<DeviceReset>), P::Error> {
+        let status = self.drv.load_status(i2c).await?;
+        let reset = match self.config.get(Enable::ADDR) {
+            Some(expected) => {
+                let found = self.drv.load_enable(i2c).await?;
+                mismatch(Enable::ADDR, expected, found.into())
+            }
+            None => None,
+        };
+        Ok((status, self.restore(i2c, reset).await?))
+    }
+
+    async fn restore<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+        reset: Option<DeviceReset>,
+    ) -> Result<Option<DeviceReset>, P::Error> {
+        if reset.is_some() {
+            self.resets += 1;
+            self.apply(i2c).await?;
+        }
+        Ok(reset)
+    }
+}
+
+fn mismatch(reg: u8, expected: u8, found: u8) -> Option<DeviceReset> {
+    let mask = retained_bits(reg);
+    if expected & mask == found & mask { None } else { Some(DeviceReset { reg, expected, found }) }
+}
@@ -0,0 +1,247 @@
+//! A minimal `no_std` async mutex.
+
+use alloc::boxed::Box;
+use core::{
+    cell::UnsafeCell,
+    future::Future,
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+    pin::Pin,
+    ptr,
+    sync::atomic::{AtomicBool, AtomicPtr, Ordering},
+    task::{Context, Poll, Waker},
+};
+
+/// An async mutual exclusion lock.
+///
+/// Waiting tasks push their wakers onto a lock-free stack, and the whole
+/// stack is taken and woken when the lock is released. Pushing never waits
+/// for another task, so a task preempted in the middle of a lock attempt
+/// can't block a higher priority task.
+pub(crate) struct Mutex<T> {
+    locked: AtomicBool,
+    waiters: AtomicPtr<Waiter>,
+    value: UnsafeCell<T>,
+}
+
+/// A guard that releases the [`Mutex`] when dropped.
+pub(crate) struct MutexGuard<'a, T> {
+    mutex: &'a Mutex<T>,
+}
+
+/// A future returned by [`Mutex::lock`].
+pub(crate) struct MutexLock<'a, T> {
+    mutex: &'a Mutex<T>,
+}
+
+struct Waiter {
+    waker: Waker,
+    next: *mut Waiter,
+}
+
+unsafe impl<T: Send> Send for Mutex<T> {}
+unsafe impl<T: Send> Sync for Mutex<T> {}
+
+impl<T> Mutex<T> {
+    /// Creates a new unlocked mutex.
+    pub(crate) fn new(value: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            waiters: AtomicPtr::new(ptr::null_mut()),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Acquires the lock, waiting until it's available.
+    pub(crate) fn lock(&self) -> MutexLock<'_, T> {
+        MutexLock { mutex: self }
+    }
+
+    /// Acquires the lock if it's available.
+    pub(crate) fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        if self.locked.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+            Some(MutexGuard { mutex: self })
+        } else {
+            None
+        }
+    }
+
+    /// Consumes the mutex, returning the underlying value.
+    pub(crate) fn into_inner(self) -> T {
+        let this = ManuallyDrop::new(self);
+        this.wake_all();
+        unsafe { ptr::read(this.value.get()) }
+    }
+
+    fn push_waiter(&self, waker: &Waker) {
+        let waiter =
+            Box::into_raw(Box::new(Waiter { waker: waker.clone(), next: ptr::null_mut() }));
+        let mut head = self.waiters.load(Ordering::SeqCst);
+        loop {
+            unsafe { (*waiter).next = head };
+            match self.waiters.compare_exchange_weak(
+                head,
+                waiter,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                Ok(_) => break,
+                Err(actual) => head = actual,
+            }
+        }
+    }
+
+    fn wake_all(&self) {
+        // Nodes are only ever removed all at once, so there is no ABA problem.
+        let mut waiter = self.waiters.swap(ptr::null_mut(), Ordering::SeqCst);
+        while !waiter.is_null() {
+            let Waiter { waker, next } = *unsafe { Box::from_raw(waiter) };
+            waker.wake();
+            waiter = next;
+        }
+    }
+}
+
+impl<T> Drop for Mutex<T> {
+    fn drop(&mut self) {
+        self.wake_all();
+    }
+}
+
+impl<'a, T> Future for MutexLock<'a, T> {
+    type Output = MutexGuard<'a, T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mutex = self.mutex;
+        if let Some(guard) = mutex.try_lock() {
+            return Poll::Ready(guard);
+        }
+        mutex.push_waiter(cx.waker());
+        // The lock could have been released before the waker was registered.
+        mutex.try_lock().map_or(Poll::Pending, Poll::Ready)
+    }
+}
+
+impl<T> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.value.get() }
+    }
+}
+
+impl<T> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.value.get() }
+    }
+}
+
+impl<T> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.mutex.locked.store(false, Ordering::SeqCst);
+        self.mutex.wake_all();
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use futures::{executor::block_on, task::ArcWake};
+    use std::{
+        sync::{atomic::AtomicUsize, Arc},
+        thread,
+    };
+
+    #[derive(Default)]
+    struct Counter(AtomicUsize);
+
+    impl ArcWake for Counter {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn try_lock() {
+        let mutex = Mutex::new(1);
+        let mut guard = mutex.try_lock().unwrap();
+        *guard += 1;
+        assert!(mutex.try_lock().is_none());
+        drop(guard);
+        assert_eq!(*mutex.try_lock().unwrap(), 2);
+    }
+
+    #[test]
+    fn wake_on_release() {
+        let mutex = Mutex::new(());
+        let counter = Arc::new(Counter::default());
+        let waker = futures::task::waker(Arc::clone(&counter));
+        let mut cx = Context::from_waker(&waker);
+        let guard = mutex.try_lock().unwrap();
+        let mut first = mutex.lock();
+        let mut second = mutex.lock();
+        assert!(Pin::new(&mut first).poll(&mut cx).is_pending());
+        assert!(Pin::new(&mut second).poll(&mut cx).is_pending());
+        assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+        drop(guard);
+        assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+        let guard = match Pin::new(&mut first).poll(&mut cx) {
+            Poll::Ready(guard) => guard,
+            Poll::Pending => panic!("not woken"),
+        };
+        assert!(Pin::new(&mut second).poll(&mut cx).is_pending());
+        drop(guard);
+        assert_eq!(counter.0.load(Ordering::SeqCst), 3);
+        assert!(Pin::new(&mut second).poll(&mut cx).is_ready());
+    }
+
+    #[test]
+    fn into_inner() {
+        let mutex = Mutex::new(vec![1, 2]);
+        mutex.try_lock().unwrap().push(3);
+        assert_eq!(mutex.into_inner(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn into_inner_drops_waiters() {
+        let mutex = Mutex::new(5);
+        let counter = Arc::new(Counter::default());
+        let waker = futures::task::waker(Arc::clone(&counter));
+        let guard = mutex.try_lock().unwrap();
+        let mut lock = mutex.lock();
+        assert!(Pin::new(&mut lock).poll(&mut Context::from_waker(&waker)).is_pending());
+        drop(lock);
+        core::mem::forget(guard);
+        drop(waker);
+        assert_eq!(mutex.into_inner(), 5);
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+        assert_eq!(Arc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn contention() {
+        const THREADS: usize = 8;
+        const ITERATIONS: usize = 1000;
+        let mutex = Arc::new(Mutex::new(0));
+        let threads = (0..THREADS)
+            .map(|_| {
+                let mutex = Arc::clone(&mutex);
+                thread::spawn(move || {
+                    for _ in 0..ITERATIONS {
+                        block_on(async {
+                            let mut guard = mutex.lock().await;
+                            let value = *guard;
+                            thread::yield_now();
+                            *guard = value + 1;
+                        });
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        let mutex = Arc::try_unwrap(mutex).ok().unwrap();
+        assert_eq!(mutex.into_inner(), THREADS * ITERATIONS);
+    }
+}
@@ -0,0 +1,225 @@
+//! Measurement cycle timing.
+//!
+//! The device state machine repeatedly runs through proximity, gesture, wait
+//! and ALS states, skipping the disabled ones. Every engine produces one
+//! sample per cycle, so the cycle time is also the sample period.
+//!
+//! The figures follow the datasheet state diagram and are approximations:
+//! internal state transitions are not accounted for. The datasheet gives
+//! only the LED pulse lengths (PPLEN and GPLEN), not the pulse period, so
+//! every pulse is assumed to be followed by an equal off time, occupying
+//! twice its length.
+
+use crate::{
+    reg::{Config1, Config2, Control, Enable, Gconf2, Gpulse, Ppulse},
+    RegisterDump,
+};
+use core::{convert::TryFrom, time::Duration};
+
+/// Duration of one ATIME integration step.
+pub const ALS_STEP: Duration = Duration::from_micros(2780);
+
+/// Duration of one WTIME step.
+pub const WAIT_STEP: Duration = Duration::from_micros(2780);
+
+/// WTIME step multiplier when [`Config1::wlong`] is set.
+pub const WAIT_LONG_FACTOR: u32 = 12;
+
+/// LED pulse lengths for PPLEN and GPLEN fields, in microseconds.
+pub(crate) const PULSE_LENGTH: [u32; 4] = [4, 8, 16, 32];
+
+/// Gesture wait times for GWTIME field, in microseconds.
+const GESTURE_WAIT: [u32; 8] = [0, 2800, 5600, 8400, 14000, 22400, 30800, 39200];
+
+/// A snapshot of the registers that affect the cycle time and the power
+/// consumption.
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    /// ENABLE register.
+    pub enable: Enable,
+    /// ATIME register.
+    pub atime: u8,
+    /// WTIME register.
+    pub wtime: u8,
+    /// CONFIG1 register.
+    pub config1: Config1,
+    /// PPULSE register.
+    pub ppulse: Ppulse,
+    /// CONTROL register.
+    pub control: Control,
+    /// CONFIG2 register.
+    pub config2: Config2,
+    /// GCONF2 register.
+    pub gconf2: Gconf2,
+    /// GPULSE register.
+    pub gpulse: Gpulse,
+}
+
+/// Time spent in each state of one measurement cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CycleTime {
+    /// Proximity accumulation time.
+    pub proximity: Duration,
+    /// Gesture accumulation and gesture wait time.
+    pub gesture: Duration,
+    /// Wait time.
+    pub wait: Duration,
+    /// ALS integration time.
+    pub als: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            enable: Enable::default(),
+            atime: 0xFF,
+            wtime: 0xFF,
+            config1: Config1::default(),
+            ppulse: Ppulse::default(),
+            control: Control::default(),
+            config2: Config2::default(),
+            gconf2: Gconf2::default(),
+            gpulse: Gpulse::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Takes the configuration from a register dump.
+    #[must_use]
+    pub fn from_dump(dump: &RegisterDump) -> Self {
+        Self {
+            enable: dump.enable(),
+            atime: dump.atime(),
+            wtime: dump.wtime(),
+            config1: dump.config1(),
+            ppulse: dump.ppulse(),
+            control: dump.control(),
+            config2: dump.config2(),
+            gconf2: dump.gconf2(),
+            gpulse: dump.gpulse(),
+        }
+    }
+
+    /// Computes the cycle time of this configuration.
+    #[must_use]
+    pub fn cycle_time(self) -> CycleTime {
+        let enable = self.enable;
+        if !enable.pon() {
+            return CycleTime::default();
+        }
+        let mut cycle = CycleTime::default();
+        if enable.pen() {
+            cycle.proximity = pulses(self.ppulse.ppulse(), self.ppulse.pplen());
+        }
+        if enable.gen() {
+            cycle.gesture = pulses(self.gpulse.gpulse(), self.gpulse.gplen())
+                + Duration::from_micros(GESTURE_WAIT[usize::from(self.gconf2.gwtime())].into());
+        }
+        if enable.wen() {
+            cycle.wait = wait_time(self.wtime, self.config1.wlong());
+        }
+        if enable.aen() {
+            cycle.als = steps(self.atime) * ALS_STEP;
+        }
+        cycle
+    }
+}
+
+impl CycleTime {
+    /// Returns the total cycle time, which is also the sample period of every
+    /// enabled engine.
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        self.proximity + self.gesture + self.wait + self.als
+    }
+}
+
+/// Proposes register values for the requested ALS and proximity sample
+/// periods.
+///
+/// Enables the engines with `Some` period and disables the others. Both
+/// engines sample once per cycle, so the shorter of the two periods is used.
+/// ATIME and pulse settings of `config` are kept, except that ATIME is
+/// shortened if the ALS integration doesn't fit into the period. The rest of
+/// the period is filled with the wait state.
+///
+/// Returns `None` if both periods are `None`, if the period is too short for
+/// the configured proximity and gesture settings, or if the wait doesn't fit
+/// into 256 long wait steps (about 8.5 s).
+#[must_use]
+pub fn solve(
+    mut config: Config,
+    als_period: Option<Duration>,
+    proximity_period: Option<Duration>,
+) -> Option<Config> {
+    let period = match (als_period, proximity_period) {
+        (Some(als), Some(proximity)) => als.min(proximity),
+        (Some(period), None) | (None, Some(period)) => period,
+        (None, None) => return None,
+    };
+    config.enable.set_pon().clear_wen();
+    if als_period.is_some() {
+        config.enable.set_aen();
+    } else {
+        config.enable.clear_aen();
+    }
+    if proximity_period.is_some() {
+        config.enable.set_pen();
+    } else {
+        config.enable.clear_pen();
+    }
+    let cycle = config.cycle_time();
+    let mut remaining = period.checked_sub(cycle.proximity + cycle.gesture)?;
+    if config.enable.aen() {
+        if cycle.als > remaining {
+            let steps = micros(remaining) / micros(ALS_STEP);
+            if steps == 0 {
+                return None;
+            }
+            config.atime = from_steps(steps);
+        }
+        remaining -= config.cycle_time().als;
+    }
+    let short = (micros(remaining) + micros(WAIT_STEP) / 2) / micros(WAIT_STEP);
+    if short == 0 {
+        return Some(config);
+    }
+    config.enable.set_wen();
+    if short <= 256 {
+        config.wtime = from_steps(short);
+        config.config1.clear_wlong();
+    } else {
+        let step = micros(WAIT_STEP) * WAIT_LONG_FACTOR;
+        let long = (micros(remaining) + step / 2) / step;
+        if long > 256 {
+            return None;
+        }
+        config.wtime = from_steps(long);
+        config.config1.set_wlong();
+    }
+    Some(config)
+}
+
+fn pulses(count: u8, length: u8) -> Duration {
+    let pulses = u32::from(count) + 1;
+    Duration::from_micros((pulses * PULSE_LENGTH[usize::from(length)] * 2).into())
+}
+
+fn wait_time(wtime: u8, wlong: bool) -> Duration {
+    let wait = steps(wtime) * WAIT_STEP;
+    if wlong { wait * WAIT_LONG_FACTOR } else { wait }
+}
+
+fn steps(value: u8) -> u32 {
+    256 - u32::from(value)
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn from_steps(steps: u32) -> u8 {
+    (256 - steps.min(256)) as u8
+}
+
+pub(crate) fn micros(duration: Duration) -> u32 {
+    u32::try_from(duration.as_micros()).unwrap_or(u32::MAX)
+}
@@ -0,0 +1,338 @@
+use crate::{
+    reg::{Enable, Gconf4},
+    Apds9960Drv, Apds9960I2CPort, Rgbc,
+};
+use core::marker::PhantomData;
+
+/// Power state of [`Apds9960`] with the device powered off.
+pub struct Off;
+
+/// Power state of [`Apds9960`] with the device powered on.
+pub struct On;
+
+/// Type-state wrapper around [`Apds9960Drv`].
+///
+/// Tracks the power state in the type parameter `S` and hands out engine
+/// handles only for enabled engines, so reading data of a disabled engine
+/// fails to compile:
+///
+/// ```no_run
+/// # use smartoris_apds9960::{Apds9960, Apds9960Drv, Apds9960I2CPort};
+/// # async fn f<P: Apds9960I2CPort<()>>(i2c: &mut P) -> Result<(), P::Error> {
+/// let sensor = Apds9960::new(Apds9960Drv::<()>::init());
+/// let mut sensor = sensor.power_on(i2c).await.map_err(|(_, err)| err)?;
+/// let mut als = sensor.enable_als(i2c).await?;
+/// let rgbc = als.load_rgbc(i2c).await?;
+/// als.disable(i2c).await?;
+/// let sensor = sensor.power_off(i2c).await.map_err(|(_, err)| err)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// The wrapper keeps a copy of ENABLE register and writes it on every
+/// transition, so ENABLE must not be modified through the inner driver.
+pub struct Apds9960<A, S> {
+    drv: Apds9960Drv<A>,
+    enable: Enable,
+    proximity: bool,
+    state: PhantomData<S>,
+}
+
+/// Handle to the enabled ALS engine.
+pub struct AlsEngine<'a, A> {
+    sensor: &'a mut Apds9960<A, On>,
+}
+
+/// Handle to the enabled proximity engine.
+pub struct ProximityEngine<'a, A> {
+    sensor: &'a mut Apds9960<A, On>,
+}
+
+/// Handle to the enabled gesture engine.
+pub struct GestureEngine<'a, A> {
+    sensor: &'a mut Apds9960<A, On>,
+}
+
+impl<A, S> Apds9960<A, S> {
+    /// Returns the inner driver for configuration registers access.
+    pub fn drv(&mut self) -> &mut Apds9960Drv<A> {
+        &mut self.drv
+    }
+
+    /// Releases the inner driver.
+    #[must_use]
+    pub fn release(self) -> Apds9960Drv<A> {
+        self.drv
+    }
+
+    fn into_state<T>(self) -> Apds9960<A, T> {
+        let Self { drv, enable, proximity, state: _ } = self;
+        Apds9960 { drv, enable, proximity, state: PhantomData }
+    }
+
+    async fn store_enable<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+        enable: Enable,
+    ) -> Result<(), P::Error> {
+        self.drv.store_enable_val(i2c, enable).await?;
+        self.enable = enable;
+        Ok(())
+    }
+}
+
+impl<A> Apds9960<A, Off> {
+    /// Wraps `drv`, assuming the device is powered off.
+    ///
+    /// The device is not accessed until [`power_on`](Apds9960::power_on).
+    #[must_use]
+    pub fn new(drv: Apds9960Drv<A>) -> Self {
+        Self { drv, enable: Enable::default(), proximity: false, state: PhantomData }
+    }
+
+    /// Powers the device on with all engines disabled.
+    ///
+    /// The device needs 5.7 ms after power on before the first cycle.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's returned together with
+    /// the unchanged wrapper.
+    pub async fn power_on<P: Apds9960I2CPort<A>>(
+        mut self,
+        i2c: &mut P,
+    ) -> Result<Apds9960<A, On>, (Self, P::Error)> {
+        match self.store_enable(i2c, *Enable::default().set_pon()).await {
+            Ok(()) => Ok(self.into_state()),
+            Err(err) => Err((self, err)),
+        }
+    }
+}
+
+impl<A> Apds9960<A, On> {
+    /// Powers the device off, disabling all engines.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's returned together with
+    /// the unchanged wrapper.
+    pub async fn power_off<P: Apds9960I2CPort<A>>(
+        mut self,
+        i2c: &mut P,
+    ) -> Result<Apds9960<A, Off>, (Self, P::Error)> {
+        match self.store_enable(i2c, Enable::default()).await {
+            Ok(()) => {
+                self.proximity = false;
+                Ok(self.into_state())
+            }
+            Err(err) => Err((self, err)),
+        }
+    }
+
+    /// Enables the ALS engine and returns its handle.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn enable_als<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+    ) -> Result<AlsEngine<'_, A>, P::Error> {
+        let mut enable = self.enable;
+        self.store_enable(i2c, *enable.set_aen()).await?;
+        Ok(AlsEngine { sensor: self })
+    }
+
+    /// Enables the proximity engine and returns its handle.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn enable_proximity<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+    ) -> Result<ProximityEngine<'_, A>, P::Error> {
+        let mut enable = self.enable;
+        self.store_enable(i2c, *enable.set_pen()).await?;
+        self.proximity = true;
+        Ok(ProximityEngine { sensor: self })
+    }
+
+    /// Enables the gesture engine in forced gesture mode and returns its
+    /// handle.
+    ///
+    /// Sets `Gconf4::gmode` and `Enable::gen`. The gesture engine runs after
+    /// the proximity state, so `Enable::pen` is set as well.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn enable_gesture<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+    ) -> Result<GestureEngine<'_, A>, P::Error> {
+        self.drv.modify::<Gconf4, _, _>(i2c, |r| r.set_gmode()).await?;
+        let mut enable = self.enable;
+        self.store_enable(i2c, *enable.set_pen().set_gen()).await?;
+        Ok(GestureEngine { sensor: self })
+    }
+
+    /// Returns the ALS engine handle if the engine is enabled.
+    pub fn als(&mut self) -> Option<AlsEngine<'_, A>> {
+        if self.enable.aen() { Some(AlsEngine { sensor: self }) } else { None }
+    }
+
+    /// Returns the proximity engine handle if the engine is enabled.
+    pub fn proximity(&mut self) -> Option<ProximityEngine<'_, A>> {
+        if self.proximity { Some(ProximityEngine { sensor: self }) } else { None }
+    }
+
+    /// Returns the gesture engine handle if the engine is enabled.
+    pub fn gesture(&mut self) -> Option<GestureEngine<'_, A>> {
+        if self.enable.gen() { Some(GestureEngine { sensor: self }) } else { None }
+    }
+}
+
+impl<A> AlsEngine<'_, A> {
+    /// Returns `true` if `Status::avalid` is set.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn is_valid<P: Apds9960I2CPort<A>>(&mut self, i2c: &mut P) -> Result<bool, P::Error> {
+        Ok(self.sensor.drv.load_status(i2c).await?.avalid())
+    }
+
+    /// Reads RGBC data.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn load_rgbc<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+    ) -> Result<Rgbc, P::Error> {
+        self.sensor.drv.load_rgbc(i2c).await
+    }
+
+    /// Clears the ALS interrupt.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn clear_interrupt<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+    ) -> Result<(), P::Error> {
+        self.sensor.drv.touch_ciclear(i2c).await
+    }
+
+    /// Disables the ALS engine.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn disable<P: Apds9960I2CPort<A>>(self, i2c: &mut P) -> Result<(), P::Error> {
+        let mut enable = self.sensor.enable;
+        self.sensor.store_enable(i2c, *enable.clear_aen()).await
+    }
+}
+
+impl<A> ProximityEngine<'_, A> {
+    /// Returns `true` if `Status::pvalid` is set.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn is_valid<P: Apds9960I2CPort<A>>(&mut self, i2c: &mut P) -> Result<bool, P::Error> {
+        Ok(self.sensor.drv.load_status(i2c).await?.pvalid())
+    }
+
+    /// Reads proximity data.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn load_pdata<P: Apds9960I2CPort<A>>(&mut self, i2c: &mut P) -> Result<u8, P::Error> {
+        self.sensor.drv.load_pdata(i2c).await
+    }
+
+    /// Clears the proximity interrupt.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn clear_interrupt<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+    ) -> Result<(), P::Error> {
+        self.sensor.drv.touch_piclear(i2c).await
+    }
+
+    /// Disables the proximity engine.
+    ///
+    /// `Enable::pen` stays set while the gesture engine is enabled.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn disable<P: Apds9960I2CPort<A>>(self, i2c: &mut P) -> Result<(), P::Error> {
+        let mut enable = self.sensor.enable;
+        if !enable.gen() {
+            enable.clear_pen();
+        }
+        self.sensor.store_enable(i2c, enable).await?;
+        self.sensor.proximity = false;
+        Ok(())
+    }
+}
+
+impl<A> GestureEngine<'_, A> {
+    /// Reads the number of datasets in the gesture FIFO.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn load_level<P: Apds9960I2CPort<A>>(&mut self, i2c: &mut P) -> Result<u8, P::Error> {
+        self.sensor.drv.load_gflvl(i2c).await
+    }
+
+    /// Reads all datasets from the gesture FIFO.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn drain_fifo<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+    ) -> Result<&[u8], P::Error> {
+        let level = self.sensor.drv.load_gflvl(i2c).await?;
+        self.sensor.drv.drain_fifo(i2c, level).await
+    }
+
+    /// Clears the gesture FIFO and the gesture interrupt.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn clear_fifo<P: Apds9960I2CPort<A>>(&mut self, i2c: &mut P) -> Result<(), P::Error> {
+        self.sensor.drv.modify::<Gconf4, _, _>(i2c, |r| r.set_gfifo_clr()).await
+    }
+
+    /// Disables the gesture engine and leaves gesture mode.
+    ///
+    /// `Enable::pen` stays set while the proximity engine is enabled.
+    ///
+    /// # Errors
+    ///
+    /// If `i2c` implementation returns `Err`, it's propagated to the caller.
+    pub async fn disable<P: Apds9960I2CPort<A>>(self, i2c: &mut P) -> Result<(), P::Error> {
+        let mut enable = self.sensor.enable;
+        enable.clear_gen();
+        if !self.sensor.proximity {
+            enable.clear_pen();
+        }
+        self.sensor.store_enable(i2c, enable).await?;
+        self.sensor.drv.modify::<Gconf4, _, _>(i2c, |r| r.clear_gmode()).await
+    }
+}
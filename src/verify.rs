@@ -0,0 +1,178 @@
+use crate::{
+    reg::{retained_bits, ReadableRegister, WritableRegister},
+    Apds9960Drv, Apds9960Error, Apds9960I2CPort, RegisterBatch,
+};
+
+/// A view of [`Apds9960Drv`] that verifies every write.
+///
+/// Exposes all generated `store_*` accessors, with the same signatures as on
+/// the driver, except that each write is read back and compared as
+/// [`store_verified`](Apds9960Drv::store_verified) does:
+///
+/// ```
+/// # use smartoris_apds9960::{Apds9960Drv, Apds9960Error, Apds9960I2CPort};
+/// # async fn f<P: Apds9960I2CPort<()> + Send>(
+/// #     drv: &mut Apds9960Drv<()>,
+/// #     i2c: &mut P,
+/// # ) -> Result<(), Apds9960Error<P::Error>> {
+/// let mut verified = drv.verified();
+/// verified.store_atime(i2c, 0xDB).await?;
+/// verified.store_enable(i2c, |r| r.set_pon().set_aen()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Apds9960Verified<'a, A> {
+    pub(crate) drv: &'a mut Apds9960Drv<A>,
+}
+
+impl<A> Apds9960Verified<'_, A> {
+    /// Writes `value` to `R` register, then reads it back and compares.
+    ///
+    /// # Errors
+    ///
+    /// See [`store_verified`](Apds9960Drv::store_verified).
+    pub async fn store<R, P>(
+        &mut self,
+        i2c: &mut P,
+        value: R,
+    ) -> Result<(), Apds9960Error<P::Error>>
+    where
+        R: ReadableRegister + WritableRegister,
+        P: Apds9960I2CPort<A>,
+    {
+        self.drv.store_verified(i2c, value).await
+    }
+
+    /// Reads contents of `R` register, passes it to the closure `f`, then
+    /// writes the result back and verifies it.
+    ///
+    /// # Errors
+    ///
+    /// See [`modify_verified`](Apds9960Drv::modify_verified).
+    pub async fn modify<R, P, F>(
+        &mut self,
+        i2c: &mut P,
+        f: F,
+    ) -> Result<(), Apds9960Error<P::Error>>
+    where
+        R: ReadableRegister + WritableRegister,
+        P: Apds9960I2CPort<A>,
+        F: FnOnce(&mut R) -> &mut R,
+    {
+        self.drv.modify_verified(i2c, f).await
+    }
+
+    /// Writes all registers from `batch`, verifying each run.
+    ///
+    /// # Errors
+    ///
+    /// See [`store_batch_verified`](Apds9960Drv::store_batch_verified).
+    pub async fn store_batch<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+        batch: &RegisterBatch,
+    ) -> Result<(), Apds9960Error<P::Error>> {
+        self.drv.store_batch_verified(i2c, batch).await
+    }
+}
+
+impl<A> Apds9960Drv<A> {
+    /// Returns a view of the driver whose `store_*` accessors verify every
+    /// write. See [`Apds9960Verified`].
+    pub fn verified(&mut self) -> Apds9960Verified<'_, A> {
+        Apds9960Verified { drv: self }
+    }
+
+    /// Writes `value` to `R` register, then reads it back and compares.
+    ///
+    /// Reserved bits and bits changed by the device itself,
+    /// `Gconf4::gfifo_clr` and `Gconf4::gmode`, are ignored in the comparison.
+    ///
+    /// The plain `store_*` accessors, [`store`](Apds9960Drv::store) and
+    /// [`store_batch`](Apds9960Drv::store_batch) never read back. To verify
+    /// writes made through the generated accessors, call them on
+    /// [`verified`](Apds9960Drv::verified) instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Apds9960Error::VerifyFailed`] if the read back value differs.
+    /// If `i2c` implementation returns `Err`, it's propagated as
+    /// [`Apds9960Error::Port`].
+    pub async fn store_verified<R, P>(
+        &mut self,
+        i2c: &mut P,
+        value: R,
+    ) -> Result<(), Apds9960Error<P::Error>>
+    where
+        R: ReadableRegister + WritableRegister,
+        P: Apds9960I2CPort<A>,
+    {
+        self.store(i2c, value).await.map_err(Apds9960Error::Port)?;
+        self.verify(i2c, R::ADDR, &value.into_raw().to_le_bytes()[..R::SIZE]).await
+    }
+
+    /// Reads contents of `R` register, passes it to the closure `f`, then
+    /// writes the result of the closure back to the register and verifies it
+    /// as [`store_verified`](Apds9960Drv::store_verified) does.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Apds9960Error::VerifyFailed`] if the read back value differs.
+    /// If `i2c` implementation returns `Err`, it's propagated as
+    /// [`Apds9960Error::Port`].
+    pub async fn modify_verified<R, P, F>(
+        &mut self,
+        i2c: &mut P,
+        f: F,
+    ) -> Result<(), Apds9960Error<P::Error>>
+    where
+        R: ReadableRegister + WritableRegister,
+        P: Apds9960I2CPort<A>,
+        F: FnOnce(&mut R) -> &mut R,
+    {
+        let mut value = self.load::<R, P>(i2c).await.map_err(Apds9960Error::Port)?;
+        f(&mut value);
+        self.store_verified(i2c, value).await
+    }
+
+    /// Writes all registers from `batch` as
+    /// [`store_batch`](Apds9960Drv::store_batch) does, reading back each run
+    /// right after it's written.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Apds9960Error::VerifyFailed`] for the first register that
+    /// differs. If `i2c` implementation returns `Err`, it's propagated as
+    /// [`Apds9960Error::Port`]. Runs written before the failure remain
+    /// applied.
+    pub async fn store_batch_verified<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+        batch: &RegisterBatch,
+    ) -> Result<(), Apds9960Error<P::Error>> {
+        for run in batch.runs() {
+            let bytes = run.iter().map(|&(_, byte)| byte).collect::<Vec<_>>();
+            self.store_burst(i2c, run[0].0, bytes.iter().copied())
+                .await
+                .map_err(Apds9960Error::Port)?;
+            self.verify(i2c, run[0].0, &bytes).await?;
+        }
+        Ok(())
+    }
+
+    async fn verify<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+        reg: u8,
+        wrote: &[u8],
+    ) -> Result<(), Apds9960Error<P::Error>> {
+        let read = self.load_burst(i2c, reg, wrote.len()).await.map_err(Apds9960Error::Port)?;
+        for ((reg, &wrote), &read) in (reg..).zip(wrote).zip(read) {
+            let mask = retained_bits(reg);
+            if wrote & mask != read & mask {
+                return Err(Apds9960Error::VerifyFailed { reg, wrote, read });
+            }
+        }
+        Ok(())
+    }
+}
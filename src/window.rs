@@ -0,0 +1,76 @@
+use crate::{
+    reg::{Aiht, Ailt, Piht, Pilt},
+    Apds9960Drv, Apds9960Error, Apds9960I2CPort, RegisterBatch,
+};
+
+impl<A> Apds9960Drv<A> {
+    /// Sets the ALS interrupt threshold window.
+    ///
+    /// AILTL, AILTH, AIHTL and AIHTH registers are written in a single burst,
+    /// so the device never observes a half-updated window. If `clear` is
+    /// `true`, a pending ALS interrupt is cleared afterwards with CICLEAR.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Apds9960Error::InvalidWindow`] without touching the device if
+    /// `low` is greater than `high`. If `i2c` implementation returns `Err`, it's
+    /// propagated as [`Apds9960Error::Port`].
+    pub async fn set_als_window<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+        low: u16,
+        high: u16,
+        clear: bool,
+    ) -> Result<(), Apds9960Error<P::Error>> {
+        if low > high {
+            return Err(Apds9960Error::InvalidWindow { low, high });
+        }
+        let mut batch = RegisterBatch::new();
+        batch.push(Ailt(low)).push(Aiht(high));
+        self.store_batch(i2c, &batch).await.map_err(Apds9960Error::Port)?;
+        if clear {
+            self.touch_ciclear(i2c).await.map_err(Apds9960Error::Port)?;
+        }
+        Ok(())
+    }
+
+    /// Sets the proximity interrupt threshold window.
+    ///
+    /// PILT and PIHT registers are separated by a reserved address, so they
+    /// are written separately. The current PIHT is read first to order the
+    /// writes: when the high threshold doesn't go down, PIHT is written
+    /// first, otherwise PILT is. Either way the intermediate window contains
+    /// the old or the new window, so no value inside both of them triggers a
+    /// spurious interrupt between the writes.
+    /// If `clear` is `true`, a pending proximity interrupt is cleared
+    /// afterwards with PICLEAR.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Apds9960Error::InvalidWindow`] without touching the device if
+    /// `low` is greater than `high`. If `i2c` implementation returns `Err`, it's
+    /// propagated as [`Apds9960Error::Port`].
+    pub async fn set_proximity_window<P: Apds9960I2CPort<A>>(
+        &mut self,
+        i2c: &mut P,
+        low: u8,
+        high: u8,
+        clear: bool,
+    ) -> Result<(), Apds9960Error<P::Error>> {
+        if low > high {
+            return Err(Apds9960Error::InvalidWindow { low: low.into(), high: high.into() });
+        }
+        let piht = self.load_piht(i2c).await.map_err(Apds9960Error::Port)?;
+        if high >= piht {
+            self.store(i2c, Piht(high)).await.map_err(Apds9960Error::Port)?;
+            self.store(i2c, Pilt(low)).await.map_err(Apds9960Error::Port)?;
+        } else {
+            self.store(i2c, Pilt(low)).await.map_err(Apds9960Error::Port)?;
+            self.store(i2c, Piht(high)).await.map_err(Apds9960Error::Port)?;
+        }
+        if clear {
+            self.touch_piclear(i2c).await.map_err(Apds9960Error::Port)?;
+        }
+        Ok(())
+    }
+}
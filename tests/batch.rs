@@ -0,0 +1,47 @@
+#![cfg(feature = "std")]
+
+use futures::executor::block_on;
+use smartoris_apds9960::{
+    adapters::{Adapters, Apds9960Sim, Operation, Trace, Transaction},
+    reg::{Atime, Config1, Control, Enable, Pers, Piht, Pilt, Wtime},
+    Apds9960Drv, RegisterBatch,
+};
+
+fn drv() -> Apds9960Drv<Adapters> {
+    Apds9960Drv::init()
+}
+
+#[test]
+fn transactions() {
+    let mut batch = RegisterBatch::new();
+    assert!(batch.is_empty());
+    assert_eq!(batch.transactions(), 0);
+    batch.push(Atime(0xDB));
+    assert_eq!(batch.transactions(), 1);
+    // WTIME is separated from ATIME by a reserved address.
+    batch.push(Wtime(0xF6));
+    assert_eq!(batch.transactions(), 2);
+    batch.push(*Enable::default().set_pon()).push(Atime(0xC0));
+    assert_eq!(batch.transactions(), 2);
+    batch.push(Config1::default()).push(Control::default()).push(Pilt(1)).push(Piht(2));
+    assert_eq!(batch.transactions(), 6);
+}
+
+#[test]
+fn store_batch() {
+    let mut batch = RegisterBatch::new();
+    batch.push(*Enable::default().set_pon()).push(Atime(0xDB)).push(Wtime(0xF6));
+    batch.push(*Pers::default().write_apers(2));
+    let mut writes = Vec::new();
+    let mut i2c = Trace::new(Apds9960Sim::new(), |transaction: &Transaction<'_>| {
+        assert_eq!(transaction.op, Operation::Store);
+        writes.push((transaction.reg, transaction.bytes.to_vec()));
+    });
+    block_on(drv().store_batch(&mut i2c, &batch)).unwrap();
+    let (sim, _) = i2c.release();
+    assert_eq!(writes, [(0x80, vec![0x01, 0xDB]), (0x83, vec![0xF6]), (0x8C, vec![0x02])]);
+    assert_eq!(sim.reg(0x80), 0x01);
+    assert_eq!(sim.reg(0x81), 0xDB);
+    assert_eq!(sim.reg(0x83), 0xF6);
+    assert_eq!(sim.reg(0x8C), 0x02);
+}
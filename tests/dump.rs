@@ -0,0 +1,55 @@
+#![cfg(feature = "std")]
+
+use futures::executor::block_on;
+use smartoris_apds9960::{
+    adapters::{Adapters, Apds9960Sim},
+    Apds9960Drv,
+};
+
+fn drv() -> Apds9960Drv<Adapters> {
+    Apds9960Drv::init()
+}
+
+#[test]
+fn dump_registers() {
+    let mut sim = Apds9960Sim::new();
+    sim.set_rgbc(1000, 100, 200, 300);
+    sim.push_gesture([1, 2, 3, 4]);
+    sim.push_gesture([5, 6, 7, 8]);
+    let dump = block_on(drv().dump_registers(&mut sim, false)).unwrap();
+    assert_eq!(dump.id(), 0xAB);
+    assert_eq!(dump.atime(), 0xFF);
+    assert_eq!(dump.cdata(), 1000);
+    assert_eq!(dump.bdata(), 300);
+    assert!(dump.status().avalid());
+    assert_eq!(dump.gflvl(), 2);
+    assert!(dump.gfifo().is_empty());
+    assert_eq!(dump.raw(0x92), Some(0xAB));
+    assert_eq!(dump.raw(0xFC), None);
+    let dump = block_on(drv().dump_registers(&mut sim, true)).unwrap();
+    assert_eq!(dump.gfifo(), [[1, 2, 3, 4], [5, 6, 7, 8]]);
+    assert_eq!(sim.reg(0xAE), 0);
+}
+
+#[test]
+fn drain_fifo_clamps_level() {
+    let mut sim = Apds9960Sim::new();
+    for i in 0..40 {
+        sim.push_gesture([i; 4]);
+    }
+    let mut drv = drv();
+    let data = block_on(drv.drain_fifo(&mut sim, 0xFF)).unwrap();
+    assert_eq!(data.len(), 32 * 4);
+    assert_eq!(&data[124..], [31; 4]);
+}
+
+#[test]
+fn display() {
+    let mut sim = Apds9960Sim::new();
+    sim.set_reg(0x80, 0x05);
+    let dump = block_on(drv().dump_registers(&mut sim, false)).unwrap();
+    let text = dump.to_string();
+    assert!(text.contains("0x05: pon=1 aen=0 pen=1 wen=0"));
+    assert!(text.contains("0xff: integration=2.78ms"));
+    assert!(text.contains("GSTATUS"));
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "std")]
+
+use async_trait::async_trait;
+use core::time::Duration;
+use futures::{executor::block_on, future, FutureExt};
+use smartoris_apds9960::{
+    adapters::{Adapters, Apds9960Sim, SharedBus},
+    Apds9960DelayPort, Apds9960Drv, Apds9960IntPort, IntWiringReport,
+};
+
+const TIMEOUT: Duration = Duration::from_millis(10);
+
+/// State of the INT line.
+#[derive(Clone, Copy)]
+enum Wiring {
+    /// Driven by the device and pulled up.
+    Connected,
+    /// Not connected to the device.
+    Open,
+    /// Shorted to ground.
+    Shorted,
+}
+
+/// An INT line following the simulated device.
+struct Line {
+    bus: SharedBus<Apds9960Sim>,
+    wiring: Wiring,
+}
+
+#[async_trait]
+impl Apds9960IntPort<Adapters> for Line {
+    fn is_low(&mut self) -> bool {
+        match self.wiring {
+            Wiring::Connected => {
+                let sim = self.bus.lock().now_or_never().unwrap();
+                sim.reg(0x80) & 1 << 4 != 0 && sim.reg(0x93) & 1 << 4 != 0
+            }
+            Wiring::Open => false,
+            Wiring::Shorted => true,
+        }
+    }
+
+    async fn wait_low(&mut self) {
+        if !self.is_low() {
+            future::pending().await
+        }
+    }
+}
+
+/// A delay port without a clock that returns immediately.
+struct NoClock;
+
+#[async_trait]
+impl Apds9960DelayPort<Adapters> for NoClock {
+    async fn delay(&mut self, _duration: Duration) {}
+}
+
+fn test_int_wiring(wiring: Wiring) -> (IntWiringReport, u8) {
+    let bus = SharedBus::new(Apds9960Sim::new());
+    block_on(bus.lock()).set_reg(0x80, 0x05);
+    let mut i2c = bus.clone();
+    let mut int = Line { bus: bus.clone(), wiring };
+    let mut drv = Apds9960Drv::<Adapters>::init();
+    let report = block_on(drv.test_int_wiring(&mut i2c, &mut NoClock, &mut int, TIMEOUT)).unwrap();
+    let enable = block_on(bus.lock()).reg(0x80);
+    (report, enable)
+}
+
+#[test]
+fn connected() {
+    let (report, enable) = test_int_wiring(Wiring::Connected);
+    assert_eq!(report, IntWiringReport { idle_high: true, asserted: true, released: true });
+    assert!(report.passed());
+    assert_eq!(enable, 0x05);
+}
+
+#[test]
+fn open() {
+    let (report, enable) = test_int_wiring(Wiring::Open);
+    assert_eq!(report, IntWiringReport { idle_high: true, asserted: false, released: true });
+    assert!(!report.passed());
+    assert_eq!(enable, 0x05);
+}
+
+#[test]
+fn shorted() {
+    let (report, _) = test_int_wiring(Wiring::Shorted);
+    assert_eq!(report, IntWiringReport { idle_high: false, asserted: true, released: false });
+    assert!(!report.passed());
+}
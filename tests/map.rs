@@ -0,0 +1,34 @@
+use smartoris_apds9960::reg::{Access, RegisterInfo};
+
+#[test]
+fn register_info_lookup() {
+    let info = RegisterInfo::by_name("ENABLE").unwrap();
+    assert_eq!(info.addr(), 0x80);
+    assert_eq!(info.size(), 1);
+    assert_eq!(info.access(), Access::ReadWrite);
+    assert_eq!(RegisterInfo::by_addr(0x80).unwrap().name(), "ENABLE");
+    assert_eq!(RegisterInfo::by_name("AILT").unwrap().size(), 2);
+    assert!(RegisterInfo::by_addr(0x82).is_none());
+    assert!(RegisterInfo::by_name("NONE").is_none());
+}
+
+#[test]
+fn field_info() {
+    let info = RegisterInfo::by_name("CONTROL").unwrap();
+    let field = info.fields().iter().find(|field| field.name() == "ldrive").unwrap();
+    assert_eq!((field.offset(), field.width()), (6, 2));
+    assert_eq!(field.mask(), 0xC0);
+    assert_eq!(field.extract(0x80), 2);
+}
+
+#[test]
+fn register_info_validate() {
+    let validate = |name, value| RegisterInfo::by_name(name).unwrap().validate(value);
+    assert!(validate("ATIME", 0xFF));
+    assert!(!validate("ATIME", 0x100));
+    assert!(validate("AILT", 0xFFFF));
+    assert!(validate("ENABLE", 0x7F));
+    assert!(!validate("ENABLE", 0x80));
+    assert!(validate("CONFIG1", 0x62));
+    assert!(!validate("ID", 0xAB));
+}
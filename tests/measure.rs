@@ -0,0 +1,128 @@
+#![cfg(feature = "std")]
+
+use async_trait::async_trait;
+use core::time::Duration;
+use futures::executor::block_on;
+use smartoris_apds9960::{
+    adapters::{Adapters, Apds9960Sim, Operation, SimError, StdDelay, Trace, Transaction},
+    reg::{Enable, Status},
+    Apds9960DelayPort, Apds9960Drv, Apds9960Error, Rgbc, MIN_POLL_INTERVAL,
+};
+
+const TIMEOUT: Duration = Duration::from_millis(100);
+
+fn drv() -> Apds9960Drv<Adapters> {
+    Apds9960Drv::init()
+}
+
+/// A delay port without a clock that returns immediately.
+struct NoClock;
+
+#[async_trait]
+impl Apds9960DelayPort<Adapters> for NoClock {
+    async fn delay(&mut self, _duration: Duration) {}
+}
+
+/// Runs `wait_status` for `Status::pvalid` without a clock, returning the
+/// result and the number of STATUS reads.
+fn wait_pvalid(sim: Apds9960Sim) -> (Result<Status, Apds9960Error<SimError>>, usize) {
+    let mut polls = 0;
+    let mut i2c = Trace::new(sim, |transaction: &Transaction<'_>| {
+        if transaction.op == Operation::Load && transaction.reg == 0x93 {
+            polls += 1;
+        }
+    });
+    let result = block_on(drv().wait_status(&mut i2c, &mut NoClock, TIMEOUT, Status::pvalid));
+    drop(i2c);
+    (result, polls)
+}
+
+#[test]
+fn load_rgbc() {
+    let mut sim = Apds9960Sim::new();
+    sim.set_rgbc(400, 100, 200, 300);
+    let rgbc = block_on(drv().load_rgbc(&mut sim));
+    assert_eq!(rgbc, Ok(Rgbc { clear: 400, red: 100, green: 200, blue: 300 }));
+}
+
+#[test]
+fn measure_rgbc_once() {
+    let mut sim = Apds9960Sim::new();
+    sim.set_rgbc(400, 100, 200, 300);
+    let rgbc = block_on(drv().measure_rgbc_once(&mut sim, &mut StdDelay::new(), TIMEOUT));
+    assert_eq!(rgbc, Ok(Rgbc { clear: 400, red: 100, green: 200, blue: 300 }));
+    assert_eq!(sim.reg(0x80), 0x00);
+}
+
+#[test]
+fn measure_rgbc_once_restores_enable() {
+    let mut sim = Apds9960Sim::new();
+    sim.set_reg(0x80, 0x45);
+    sim.set_rgbc(1, 2, 3, 4);
+    block_on(drv().measure_rgbc_once(&mut sim, &mut StdDelay::new(), TIMEOUT)).unwrap();
+    assert_eq!(sim.reg(0x80), 0x45);
+}
+
+#[test]
+fn measure_proximity_once() {
+    let mut sim = Apds9960Sim::new();
+    sim.set_pdata(0x42);
+    let pdata = block_on(drv().measure_proximity_once(&mut sim, &mut StdDelay::new(), TIMEOUT));
+    assert_eq!(pdata, Ok(0x42));
+    assert_eq!(sim.reg(0x80), 0x00);
+}
+
+#[test]
+fn measure_proximity_once_timeout() {
+    let mut sim = Apds9960Sim::new();
+    let pdata = block_on(drv().measure_proximity_once(&mut sim, &mut StdDelay::new(), TIMEOUT));
+    assert_eq!(pdata, Err(Apds9960Error::Timeout));
+    assert_eq!(sim.reg(0x80), 0x00);
+}
+
+#[test]
+fn measure_enables_only_the_engine() {
+    let mut sim = Apds9960Sim::new();
+    // PON, PEN, WEN and GEN.
+    sim.set_reg(0x80, 0x4D);
+    sim.set_rgbc(1, 2, 3, 4);
+    let mut enables = Vec::new();
+    let mut i2c = Trace::new(sim, |transaction: &Transaction<'_>| {
+        if transaction.op == Operation::Store && transaction.reg == 0x80 {
+            enables.push(transaction.bytes[0]);
+        }
+    });
+    block_on(drv().measure_rgbc_once(&mut i2c, &mut StdDelay::new(), TIMEOUT)).unwrap();
+    drop(i2c);
+    assert_eq!(enables, [0x03, 0x4D]);
+}
+
+#[test]
+fn wait_status_idle() {
+    let mut sim = Apds9960Sim::new();
+    assert_eq!(wait_pvalid(sim).0.unwrap_err(), Apds9960Error::Idle);
+    sim = Apds9960Sim::new();
+    sim.set_reg(0x80, u8::from(*Enable::default().set_pon()));
+    assert_eq!(wait_pvalid(sim).0.unwrap_err(), Apds9960Error::Idle);
+}
+
+#[test]
+fn wait_status_timeout_without_clock() {
+    let mut sim = Apds9960Sim::new();
+    // A single 8 µs proximity pulse makes a cycle far below the poll floor.
+    sim.set_reg(0x80, u8::from(*Enable::default().set_pon().set_pen()));
+    sim.set_reg(0x8E, 0x00);
+    let (result, polls) = wait_pvalid(sim);
+    assert_eq!(result.unwrap_err(), Apds9960Error::Timeout);
+    assert_eq!(polls as u128, TIMEOUT.as_nanos() / MIN_POLL_INTERVAL.as_nanos());
+}
+
+#[test]
+fn wait_status_ready() {
+    let mut sim = Apds9960Sim::new();
+    sim.set_reg(0x80, u8::from(*Enable::default().set_pon().set_pen()));
+    sim.set_pdata(1);
+    let (result, polls) = wait_pvalid(sim);
+    assert!(result.unwrap().pvalid());
+    assert_eq!(polls, 1);
+}
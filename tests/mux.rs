@@ -0,0 +1,132 @@
+#![cfg(feature = "std")]
+
+use async_trait::async_trait;
+use futures::executor::block_on;
+use smartoris_apds9960::{
+    adapters::{Adapters, Apds9960Sim, I2CMux, SimError},
+    reg::Atime,
+    Apds9960Array, Apds9960Drv, Apds9960I2CPort,
+};
+
+const MUX_ADDR: u8 = I2CMux::<Bus>::DEFAULT_ADDR;
+
+/// A multiplexer with a simulated device on every channel.
+struct Bus {
+    control: u8,
+    devices: Vec<Apds9960Sim>,
+    selects: usize,
+    fail: bool,
+}
+
+impl Bus {
+    fn new() -> Self {
+        Self {
+            control: 0,
+            devices: (0..8).map(|_| Apds9960Sim::new()).collect(),
+            selects: 0,
+            fail: false,
+        }
+    }
+
+    fn device(&mut self) -> Option<&mut Apds9960Sim> {
+        match self.control.count_ones() {
+            1 => Some(&mut self.devices[self.control.trailing_zeros() as usize]),
+            _ => None,
+        }
+    }
+}
+
+#[async_trait]
+impl Apds9960I2CPort<Adapters> for Bus {
+    type Error = SimError;
+
+    async fn write(
+        &mut self,
+        addr: u8,
+        buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, SimError)> {
+        if self.fail {
+            return Err((buf, SimError::Nack));
+        }
+        if addr == MUX_ADDR {
+            self.control = buf[0];
+            self.selects += 1;
+            return Ok(buf);
+        }
+        match self.device() {
+            Some(device) => device.write(addr, buf, count).await,
+            None => Err((buf, SimError::Nack)),
+        }
+    }
+
+    async fn read(
+        &mut self,
+        addr: u8,
+        buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, SimError)> {
+        if self.fail {
+            return Err((buf, SimError::Nack));
+        }
+        match self.device() {
+            Some(device) => device.read(addr, buf, count).await,
+            None => Err((buf, SimError::Nack)),
+        }
+    }
+}
+
+fn drv() -> Apds9960Drv<Adapters> {
+    Apds9960Drv::init()
+}
+
+#[test]
+fn select_once_per_channel() {
+    let mut mux = I2CMux::new(Bus::new(), MUX_ADDR);
+    let mut drv = drv();
+    assert_eq!(mux.active(), None);
+    block_on(drv.load_id(&mut mux.channel(3))).unwrap();
+    block_on(drv.load_id(&mut mux.channel(3))).unwrap();
+    assert_eq!(mux.active(), Some(3));
+    block_on(drv.store(&mut mux.channel(0), Atime(0xDB))).unwrap();
+    assert_eq!(mux.active(), Some(0));
+    let bus = mux.release();
+    assert_eq!(bus.selects, 2);
+    assert_eq!(bus.devices[0].reg(0x81), 0xDB);
+    assert_eq!(bus.devices[3].reg(0x81), 0xFF);
+}
+
+#[test]
+fn select_failed() {
+    let mut mux = I2CMux::new(Bus::new(), MUX_ADDR);
+    let mut drv = drv();
+    block_on(drv.load_id(&mut mux.channel(1))).unwrap();
+    assert_eq!(mux.active(), Some(1));
+    let mut bus = mux.release();
+    bus.fail = true;
+    let mut mux = I2CMux::new(bus, MUX_ADDR);
+    block_on(mux.select(1)).unwrap_err();
+    assert_eq!(mux.active(), None);
+}
+
+#[test]
+#[should_panic]
+fn invalid_channel() {
+    I2CMux::new(Bus::new(), MUX_ADDR).channel(8);
+}
+
+#[test]
+fn array() {
+    let mut bus = Bus::new();
+    bus.devices[2].set_rgbc(1, 2, 3, 4);
+    bus.devices[5].set_pdata(0x42);
+    let mut array = Apds9960Array::<Adapters, _>::new(I2CMux::new(bus, MUX_ADDR), vec![2, 5]);
+    assert_eq!(array.len(), 2);
+    assert_eq!(block_on(array.load_id_all()), Ok(vec![0xAB, 0xAB]));
+    let rgbc = block_on(array.load_rgbc_all()).unwrap();
+    assert_eq!((rgbc[0].clear, rgbc[1].clear), (1, 0));
+    assert_eq!(block_on(array.load_pdata_all()), Ok(vec![0, 0x42]));
+    let (drv, mut i2c) = array.get(1).unwrap();
+    block_on(drv.store(&mut i2c, Atime(0x10))).unwrap();
+    assert_eq!(array.release().release().devices[5].reg(0x81), 0x10);
+}
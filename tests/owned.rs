@@ -0,0 +1,56 @@
+#![cfg(feature = "std")]
+
+use futures::executor::block_on;
+use smartoris_apds9960::{
+    adapters::{Adapters, Apds9960Sim, SimError},
+    reg::{Atime, Control, Wtime},
+    Apds9960Drv, Apds9960I2CPort, Apds9960Owned, RegisterBatch,
+};
+
+fn owned<P: Apds9960I2CPort<Adapters>>(i2c: P) -> Apds9960Owned<Adapters, P> {
+    Apds9960Owned::new(Apds9960Drv::init(), i2c)
+}
+
+#[test]
+fn accessors() {
+    let mut apds9960 = owned(Apds9960Sim::new());
+    assert_eq!(block_on(apds9960.load_id()), Ok(0xAB));
+    block_on(apds9960.store_enable(|r| r.set_pon().set_aen())).unwrap();
+    block_on(apds9960.store_atime(0xDB)).unwrap();
+    assert!(block_on(apds9960.load_enable()).unwrap().aen());
+    assert_eq!(block_on(apds9960.load::<Atime>()), Ok(Atime(0xDB)));
+    block_on(apds9960.modify(|r: &mut Control| r.write_ldrive(2))).unwrap();
+    let (_, sim) = apds9960.release();
+    assert_eq!((sim.reg(0x80), sim.reg(0x81), sim.reg(0x8F)), (0x03, 0xDB, 0x80));
+}
+
+#[test]
+fn borrowed_port() {
+    let mut sim = Apds9960Sim::new();
+    sim.set_rgbc(1000, 100, 200, 300);
+    sim.push_gesture([1, 2, 3, 4]);
+    let mut apds9960 = owned(&mut sim);
+    assert_eq!(block_on(apds9960.load_rgbc()).unwrap().clear, 1000);
+    assert_eq!(block_on(apds9960.drain_fifo(1)).unwrap(), [1, 2, 3, 4]);
+    let mut batch = RegisterBatch::new();
+    batch.push(Atime(0xC0)).push(Wtime(0xF6));
+    block_on(apds9960.store_batch(&batch)).unwrap();
+    let dump = block_on(apds9960.dump_registers(false)).unwrap();
+    assert_eq!(dump.atime(), 0xC0);
+    drop(apds9960);
+    assert_eq!(sim.reg(0x83), 0xF6);
+}
+
+#[test]
+fn parts_and_address() {
+    let mut apds9960 = owned(Apds9960Sim::new());
+    {
+        let (drv, sim) = apds9960.parts();
+        sim.fail_next(1);
+        assert_eq!(block_on(drv.load_id(sim)), Err(SimError::Nack));
+    }
+    apds9960.set_addr(0x3A);
+    assert_eq!(block_on(apds9960.load_id()), Err(SimError::Nack));
+    apds9960.set_addr(0x39);
+    assert_eq!(block_on(apds9960.load_id()), Ok(0xAB));
+}
@@ -0,0 +1,50 @@
+use core::time::Duration;
+use smartoris_apds9960::reg::{AlsPersistence, Pers, ProximityPersistence};
+
+#[test]
+fn als_persistence_nearest() {
+    assert_eq!(AlsPersistence::nearest(0), AlsPersistence::Cycles1);
+    assert_eq!(AlsPersistence::nearest(3), AlsPersistence::Cycles3);
+    assert_eq!(AlsPersistence::nearest(4), AlsPersistence::Cycles3);
+    assert_eq!(AlsPersistence::nearest(7), AlsPersistence::Cycles5);
+    assert_eq!(AlsPersistence::nearest(12), AlsPersistence::Cycles10);
+    assert_eq!(AlsPersistence::nearest(13), AlsPersistence::Cycles15);
+    assert_eq!(AlsPersistence::nearest(1000), AlsPersistence::Cycles60);
+}
+
+#[test]
+fn als_persistence_for_duration() {
+    let cycle = Duration::from_millis(10);
+    assert_eq!(
+        AlsPersistence::for_duration(Duration::from_millis(100), cycle),
+        AlsPersistence::Cycles10
+    );
+    assert_eq!(AlsPersistence::for_duration(Duration::default(), cycle), AlsPersistence::Cycles1);
+}
+
+#[test]
+fn als_persistence_bits() {
+    for bits in 0..16 {
+        assert_eq!(AlsPersistence::from_bits(bits).bits(), bits);
+    }
+    assert_eq!(AlsPersistence::Every.cycles(), 0);
+    assert_eq!(AlsPersistence::Cycles5.cycles(), 5);
+    assert_eq!(AlsPersistence::Cycles60.cycles(), 60);
+}
+
+#[test]
+fn proximity_persistence_nearest() {
+    assert_eq!(ProximityPersistence::nearest(0), ProximityPersistence::Cycles1);
+    assert_eq!(ProximityPersistence::nearest(7), ProximityPersistence::Cycles7);
+    assert_eq!(ProximityPersistence::nearest(100), ProximityPersistence::Cycles15);
+}
+
+#[test]
+fn pers_fields() {
+    let mut pers = Pers::default();
+    pers.write_als_persistence(AlsPersistence::Cycles10)
+        .write_proximity_persistence(ProximityPersistence::Cycles3);
+    assert_eq!(u8::from(pers), 0x35);
+    assert_eq!(pers.als_persistence(), AlsPersistence::Cycles10);
+    assert_eq!(pers.proximity_persistence(), ProximityPersistence::Cycles3);
+}
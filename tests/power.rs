@@ -0,0 +1,53 @@
+use smartoris_apds9960::{
+    power::{self, PowerEstimate, ACTIVE_CURRENT, PULSE_CURRENT, SLEEP_CURRENT, WAIT_CURRENT},
+    timing::Config,
+};
+
+#[test]
+fn estimate_off() {
+    assert_eq!(power::estimate(Config::default()), PowerEstimate { supply: SLEEP_CURRENT, led: 0 });
+}
+
+#[test]
+fn estimate_idle() {
+    let mut config = Config::default();
+    config.enable.set_pon();
+    assert_eq!(power::estimate(config), PowerEstimate { supply: WAIT_CURRENT, led: 0 });
+}
+
+#[test]
+fn estimate_als_and_wait() {
+    let mut config = Config::default();
+    config.enable.set_pon().set_aen().set_wen();
+    config.atime = 0xFF;
+    config.wtime = 0xFF;
+    // Equal ALS and wait times.
+    let estimate = power::estimate(config);
+    assert_eq!(estimate, PowerEstimate { supply: (ACTIVE_CURRENT + WAIT_CURRENT) / 2, led: 0 });
+}
+
+#[test]
+fn estimate_proximity() {
+    let mut config = Config::default();
+    config.enable.set_pon().set_pen();
+    config.ppulse.write_ppulse(7).write_pplen(2);
+    // The LED is on for half of the proximity cycle.
+    let estimate = power::estimate(config);
+    assert_eq!(estimate.supply, (ACTIVE_CURRENT + PULSE_CURRENT) / 2);
+    assert_eq!(estimate.led, 100_000 / 2);
+    assert_eq!(estimate.total(), estimate.supply + estimate.led);
+    config.control.write_ldrive(1);
+    config.config2.write_led_boost(3);
+    assert_eq!(power::estimate(config).led, 50_000 * 3 / 2);
+}
+
+#[test]
+fn estimate_gesture_uses_gldrive() {
+    let mut config = Config::default();
+    config.enable.set_pon().set_gen();
+    let estimate = power::estimate(config);
+    config.control.write_ldrive(3);
+    assert_eq!(power::estimate(config), estimate);
+    config.gconf2.write_gldrive(3);
+    assert_eq!(power::estimate(config).led, estimate.led / 8);
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "std")]
+
+use core::time::Duration;
+use futures::executor::block_on;
+use smartoris_apds9960::{
+    adapters::{Adapters, Apds9960Sim, Retry, RetryPolicy, RetryStats, SimError, StdDelay},
+    reg::Atime,
+    Apds9960Drv, RetryableError,
+};
+
+fn drv() -> Apds9960Drv<Adapters> {
+    Apds9960Drv::init()
+}
+
+fn retry(sim: Apds9960Sim, policy: RetryPolicy) -> Retry<Apds9960Sim, StdDelay> {
+    let policy = RetryPolicy { backoff: Duration::from_micros(10), ..policy };
+    Retry::new(sim, StdDelay::new(), policy)
+}
+
+#[test]
+fn backoff() {
+    let policy = RetryPolicy::default();
+    assert_eq!(policy.backoff(1), Duration::from_millis(1));
+    assert_eq!(policy.backoff(3), Duration::from_millis(4));
+    assert_eq!(policy.backoff(10), Duration::from_millis(16));
+    assert_eq!(policy.backoff(100), Duration::from_millis(16));
+}
+
+#[test]
+fn retryable() {
+    assert!(SimError::Nack.is_retryable());
+}
+
+#[test]
+fn recovered() {
+    let mut i2c = retry(Apds9960Sim::new(), RetryPolicy::default());
+    i2c.port().fail_next(2);
+    assert_eq!(block_on(drv().load_id(&mut i2c)), Ok(0xAB));
+    assert_eq!(i2c.stats(), RetryStats { retries: 2, recovered: 1, ..RetryStats::default() });
+    i2c.reset_stats();
+    assert_eq!(i2c.stats(), RetryStats::default());
+}
+
+#[test]
+fn exhausted() {
+    let mut i2c = retry(Apds9960Sim::new(), RetryPolicy::default());
+    i2c.port().fail_next(3);
+    assert_eq!(block_on(drv().store(&mut i2c, Atime(0xDB))), Err(SimError::Nack));
+    assert_eq!(i2c.stats(), RetryStats { retries: 2, exhausted: 1, ..RetryStats::default() });
+    assert_eq!(i2c.port().reg(0x81), 0xFF);
+}
+
+#[test]
+fn fifo_retried() {
+    let mut sim = Apds9960Sim::new();
+    sim.push_gesture([1, 2, 3, 4]);
+    let mut i2c = retry(sim, RetryPolicy::default());
+    i2c.port().fail_next(1);
+    assert_eq!(block_on(drv().drain_fifo(&mut i2c, 1)).unwrap(), [1, 2, 3, 4]);
+    let stats = RetryStats { retries: 1, recovered: 1, fifo_failures: 1, ..RetryStats::default() };
+    assert_eq!(i2c.stats(), stats);
+}
+
+#[test]
+fn fifo_not_retried() {
+    let mut sim = Apds9960Sim::new();
+    sim.push_gesture([1, 2, 3, 4]);
+    let policy = RetryPolicy { retry_fifo_reads: false, ..RetryPolicy::default() };
+    let mut i2c = retry(sim, policy);
+    i2c.port().fail_next(1);
+    assert!(block_on(drv().drain_fifo(&mut i2c, 1)).is_err());
+    assert_eq!(i2c.stats(), RetryStats { fifo_failures: 1, ..RetryStats::default() });
+    // Register reads are still retried.
+    i2c.port().fail_next(1);
+    assert_eq!(block_on(drv().load_id(&mut i2c)), Ok(0xAB));
+    assert_eq!(i2c.stats().recovered, 1);
+}
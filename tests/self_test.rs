@@ -0,0 +1,128 @@
+#![cfg(feature = "std")]
+
+use async_trait::async_trait;
+use core::time::Duration;
+use futures::executor::block_on;
+use smartoris_apds9960::{
+    adapters::{Adapters, Apds9960Sim, SimError},
+    Apds9960DelayPort, Apds9960Drv, Apds9960I2CPort, Rgbc, SelfTestReport,
+};
+
+const TIMEOUT: Duration = Duration::from_millis(500);
+
+/// A test fixture with a reflective target above the sensor.
+///
+/// Proximity follows the LED current, and the gesture engine produces a
+/// dataset as soon as it's enabled.
+struct Fixture {
+    sim: Apds9960Sim,
+}
+
+#[async_trait]
+impl Apds9960I2CPort<Adapters> for Fixture {
+    type Error = SimError;
+
+    async fn write(
+        &mut self,
+        addr: u8,
+        buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, SimError)> {
+        let buf = self.sim.write(addr, buf, count).await?;
+        if count == 2 {
+            match buf[0] {
+                0x80 if buf[1] & 1 << 6 != 0 => self.sim.push_gesture([1, 2, 3, 4]),
+                0x8F => self.sim.set_pdata([200, 100, 50, 20][usize::from(buf[1] >> 6)]),
+                _ => {}
+            }
+        }
+        Ok(buf)
+    }
+
+    async fn read(
+        &mut self,
+        addr: u8,
+        buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, SimError)> {
+        self.sim.read(addr, buf, count).await
+    }
+}
+
+/// A delay port without a clock that returns immediately.
+struct NoClock;
+
+#[async_trait]
+impl Apds9960DelayPort<Adapters> for NoClock {
+    async fn delay(&mut self, _duration: Duration) {}
+}
+
+fn fixture(clear: u16) -> Fixture {
+    let mut sim = Apds9960Sim::new();
+    sim.set_rgbc(clear, 1, 2, 3);
+    Fixture { sim }
+}
+
+fn self_test(fixture: &mut Fixture, max_dark: u16) -> SelfTestReport {
+    let mut drv = Apds9960Drv::<Adapters>::init();
+    block_on(drv.self_test(fixture, &mut NoClock, TIMEOUT, max_dark)).unwrap()
+}
+
+#[test]
+fn passed() {
+    let mut fixture = fixture(10);
+    fixture.sim.set_reg(0x80, 0x01);
+    fixture.sim.set_reg(0x81, 0x12);
+    fixture.sim.set_reg(0x8F, 0x40);
+    let report = self_test(&mut fixture, 100);
+    assert!(report.passed(), "{:?}", report);
+    assert_eq!(report.id.value, 0xAB);
+    assert_eq!(report.readback.value, 0);
+    assert_eq!(report.led.value, [20, 200]);
+    assert_eq!(report.als.value, Rgbc { clear: 10, red: 1, green: 2, blue: 3 });
+    assert_eq!(report.gesture.value, 1);
+    assert!(!report.led_saturated && !report.als_saturated);
+    assert_eq!(fixture.sim.reg(0x80), 0x01);
+    assert_eq!(fixture.sim.reg(0x81), 0x12);
+    assert_eq!(fixture.sim.reg(0x8F), 0x40);
+}
+
+#[test]
+fn dark_limit() {
+    let report = self_test(&mut fixture(101), 100);
+    assert!(!report.als.passed);
+    assert!(self_test(&mut fixture(101), u16::MAX).als.passed);
+}
+
+#[test]
+fn digital_saturation() {
+    // 1025 counts per ATIME step, 37 steps.
+    assert!(self_test(&mut fixture(37924), u16::MAX).als.passed);
+    assert!(!self_test(&mut fixture(37925), u16::MAX).als.passed);
+}
+
+#[test]
+fn analog_saturation() {
+    let mut fixture = fixture(10);
+    fixture.sim.set_reg(0x93, fixture.sim.reg(0x93) | 1 << 7);
+    let report = self_test(&mut fixture, u16::MAX);
+    assert!(report.als_saturated && !report.als.passed);
+    assert!(!report.led_saturated && report.led.passed);
+
+    let mut fixture = self::fixture(10);
+    fixture.sim.set_reg(0x93, fixture.sim.reg(0x93) | 1 << 6);
+    let report = self_test(&mut fixture, u16::MAX);
+    assert!(report.led_saturated && !report.led.passed);
+    assert!(!report.als_saturated && report.als.passed);
+}
+
+#[test]
+fn no_data() {
+    let mut fixture = Fixture { sim: Apds9960Sim::new() };
+    let mut drv = Apds9960Drv::<Adapters>::init();
+    // Nothing sets AVALID, and PVALID is set by the fixture only.
+    let report = block_on(drv.self_test(&mut fixture, &mut NoClock, TIMEOUT, u16::MAX)).unwrap();
+    assert!(report.led.passed);
+    assert!(!report.als.passed);
+    assert_eq!(report.als.value, Rgbc::default());
+}
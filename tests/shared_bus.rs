@@ -0,0 +1,67 @@
+#![cfg(feature = "std")]
+
+use futures::{executor::block_on, FutureExt};
+use smartoris_apds9960::{
+    adapters::{Adapters, Apds9960Sim, SharedBus},
+    reg::Atime,
+    Apds9960Drv,
+};
+use std::thread;
+
+fn drv() -> Apds9960Drv<Adapters> {
+    Apds9960Drv::init()
+}
+
+#[test]
+fn handles() {
+    let bus = SharedBus::new(Apds9960Sim::new());
+    let mut first = bus.clone();
+    let mut second = bus.clone();
+    block_on(drv().store(&mut first, Atime(0xDB))).unwrap();
+    assert_eq!(block_on(drv().load_atime(&mut second)), Ok(0xDB));
+    assert_eq!(block_on(bus.lock()).reg(0x81), 0xDB);
+}
+
+#[test]
+fn lock() {
+    let bus = SharedBus::new(Apds9960Sim::new());
+    let mut i2c = bus.clone();
+    let mut drv = drv();
+    let guard = block_on(bus.lock());
+    assert!(bus.lock().now_or_never().is_none());
+    let mut load = Box::pin(drv.load_id(&mut i2c));
+    assert!((&mut load).now_or_never().is_none());
+    drop(guard);
+    assert_eq!(load.now_or_never(), Some(Ok(0xAB)));
+}
+
+#[test]
+fn try_into_inner() {
+    let bus = SharedBus::new(Apds9960Sim::new());
+    let other = bus.clone();
+    let bus = bus.try_into_inner().err().unwrap();
+    drop(other);
+    let sim = bus.try_into_inner().ok().unwrap();
+    assert_eq!(sim.reg(0x92), 0xAB);
+}
+
+#[test]
+fn threads() {
+    let bus = SharedBus::new(Apds9960Sim::new());
+    let threads = (0..4_u8)
+        .map(|i| {
+            let mut i2c = bus.clone();
+            thread::spawn(move || {
+                let mut drv = drv();
+                for _ in 0..100 {
+                    block_on(drv.store(&mut i2c, Atime(i))).unwrap();
+                    assert_eq!(block_on(drv.load_id(&mut i2c)), Ok(0xAB));
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert!(bus.try_into_inner().ok().unwrap().reg(0x81) < 4);
+}
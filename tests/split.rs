@@ -0,0 +1,69 @@
+#![cfg(feature = "std")]
+
+use futures::{executor::block_on, FutureExt};
+use smartoris_apds9960::{
+    adapters::{Adapters, Apds9960Sim, SharedBus},
+    Apds9960Drv,
+};
+
+fn bus() -> SharedBus<Apds9960Sim> {
+    SharedBus::new(Apds9960Sim::new())
+}
+
+fn reg(bus: &SharedBus<Apds9960Sim>, reg: u8) -> u8 {
+    bus.lock().now_or_never().unwrap().reg(reg)
+}
+
+#[test]
+fn enable_coordination() {
+    let bus = bus();
+    block_on(bus.lock()).set_reg(0x80, 0x10);
+    let (mut als, mut proximity, mut gesture) = Apds9960Drv::<Adapters>::init().split(bus.clone());
+    block_on(als.enable()).unwrap();
+    assert_eq!(reg(&bus, 0x80), 0x13);
+    block_on(proximity.enable()).unwrap();
+    assert_eq!(reg(&bus, 0x80), 0x17);
+    block_on(gesture.enable()).unwrap();
+    assert_eq!(reg(&bus, 0x80), 0x57);
+    assert_eq!(reg(&bus, 0xAB) & 1, 1);
+    // PEN stays set for the gesture engine.
+    block_on(proximity.disable()).unwrap();
+    assert_eq!(reg(&bus, 0x80), 0x57);
+    block_on(als.disable()).unwrap();
+    assert_eq!(reg(&bus, 0x80), 0x55);
+    // The device is powered off with the last engine, other bits are kept.
+    block_on(gesture.disable()).unwrap();
+    assert_eq!(reg(&bus, 0x80), 0x10);
+    assert_eq!(reg(&bus, 0xAB) & 1, 0);
+}
+
+#[test]
+fn parts_data() {
+    let bus = bus();
+    let (mut als, mut proximity, mut gesture) = Apds9960Drv::<Adapters>::init().split(bus.clone());
+    {
+        let mut sim = block_on(bus.lock());
+        sim.set_rgbc(1000, 100, 200, 300);
+        sim.set_pdata(42);
+        sim.push_gesture([1, 2, 3, 4]);
+        sim.push_gesture([5, 6, 7, 8]);
+    }
+    assert!(block_on(als.is_valid()).unwrap());
+    assert_eq!(block_on(als.load_rgbc()).unwrap().clear, 1000);
+    assert!(block_on(proximity.is_valid()).unwrap());
+    assert_eq!(block_on(proximity.load_pdata()).unwrap(), 42);
+    assert_eq!(block_on(gesture.load_level()).unwrap(), 2);
+    assert_eq!(block_on(gesture.drain_fifo()).unwrap(), [[1, 2, 3, 4], [5, 6, 7, 8]]);
+    block_on(bus.lock()).push_gesture([1, 2, 3, 4]);
+    block_on(gesture.clear_fifo()).unwrap();
+    assert_eq!(block_on(gesture.load_level()).unwrap(), 0);
+}
+
+#[test]
+fn unsplit() {
+    let (als, proximity, gesture) = Apds9960Drv::<Adapters>::init().split(bus());
+    let (_, other, _) = Apds9960Drv::<Adapters>::init().split(bus());
+    let (als, _, gesture) = Apds9960Drv::unsplit(als, other, gesture).err().unwrap();
+    let (mut drv, mut bus) = Apds9960Drv::unsplit(als, proximity, gesture).ok().unwrap();
+    assert_eq!(block_on(drv.load_id(&mut bus)), Ok(0xAB));
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "std")]
+
+use core::time::Duration;
+use futures::executor::block_on;
+use smartoris_apds9960::{
+    adapters::{Adapters, Apds9960Sim, Retry, RetryPolicy, SharedBus, StdDelay},
+    reg::Atime,
+    Apds9960Drv, BusStats,
+};
+
+fn drv() -> Apds9960Drv<Adapters> {
+    let mut drv = Apds9960Drv::init();
+    drv.enable_stats();
+    drv
+}
+
+#[test]
+fn disabled() {
+    let mut sim = Apds9960Sim::new();
+    let mut drv = Apds9960Drv::<Adapters>::init();
+    block_on(drv.load_id(&mut sim)).unwrap();
+    assert_eq!(drv.stats(), None);
+    drv.enable_stats();
+    assert_eq!(drv.stats(), Some(BusStats::default()));
+    drv.disable_stats();
+    assert_eq!(drv.stats(), None);
+}
+
+#[test]
+fn transactions() {
+    let mut sim = Apds9960Sim::new();
+    let mut drv = drv();
+    block_on(drv.load_id(&mut sim)).unwrap();
+    block_on(drv.load_cdata(&mut sim)).unwrap();
+    block_on(drv.store(&mut sim, Atime(0xDB))).unwrap();
+    block_on(drv.touch_aiclear(&mut sim)).unwrap();
+    let stats = drv.stats().unwrap();
+    assert_eq!(stats.transactions, 4);
+    assert_eq!(stats.bytes_read, 3);
+    assert_eq!(stats.bytes_written, 2 + 2 + 1);
+    assert_eq!(stats.errors, 0);
+    drv.reset_stats();
+    assert_eq!(drv.stats(), Some(BusStats::default()));
+}
+
+#[test]
+fn errors() {
+    let mut sim = Apds9960Sim::new();
+    let mut drv = drv();
+    sim.fail_next(3);
+    assert!(block_on(drv.load_id(&mut sim)).is_err());
+    assert!(block_on(drv.load_id(&mut sim)).is_err());
+    assert!(block_on(drv.store(&mut sim, Atime(0xDB))).is_err());
+    let stats = drv.stats().unwrap();
+    assert_eq!((stats.transactions, stats.errors), (3, 3));
+    assert_eq!((stats.bytes_read, stats.bytes_written), (0, 0));
+    assert_eq!(stats.register_errors(0x92), 2);
+    assert_eq!(stats.register_errors(0x80), 0);
+    assert_eq!(stats.errors_by_register(), [(0x81, 1), (0x92, 2)]);
+}
+
+#[test]
+fn status_flags() {
+    let mut sim = Apds9960Sim::new();
+    let mut drv = drv();
+    sim.set_reg(0x93, 0xC0);
+    sim.set_reg(0xAF, 0x02);
+    block_on(drv.load_status(&mut sim)).unwrap();
+    block_on(drv.load_status(&mut sim)).unwrap();
+    block_on(drv.load_gstatus(&mut sim)).unwrap();
+    block_on(drv.load_id(&mut sim)).unwrap();
+    let stats = drv.stats().unwrap();
+    assert_eq!(stats.proximity_saturations, 2);
+    assert_eq!(stats.clear_saturations, 2);
+    assert_eq!(stats.fifo_overflows, 1);
+}
+
+#[test]
+fn retries() {
+    let policy = RetryPolicy { backoff: Duration::from_micros(10), ..RetryPolicy::default() };
+    let mut i2c = Retry::new(Apds9960Sim::new(), StdDelay::new(), policy);
+    let mut drv = drv();
+    i2c.port().fail_next(2);
+    block_on(drv.load_id(&mut i2c)).unwrap();
+    i2c.port().fail_next(3);
+    assert!(block_on(drv.store(&mut i2c, Atime(0xDB))).is_err());
+    let stats = drv.stats().unwrap();
+    assert_eq!(stats.retries, 4);
+    assert_eq!((stats.transactions, stats.errors), (2, 1));
+    assert_eq!(i2c.stats().retries, 4);
+    // Retries below a shared bus aren't attributed to any driver.
+    let mut bus = SharedBus::new(i2c);
+    block_on(bus.lock()).port().fail_next(1);
+    block_on(drv.load_id(&mut bus)).unwrap();
+    assert_eq!(drv.stats().unwrap().retries, 4);
+}
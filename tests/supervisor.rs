@@ -0,0 +1,95 @@
+#![cfg(feature = "std")]
+
+use futures::executor::block_on;
+use smartoris_apds9960::{
+    adapters::{Adapters, Apds9960Sim, Trace, Transaction},
+    reg::{Atime, Control, Enable, Gconf4, Wtime},
+    Apds9960Drv, DeviceReset, RegisterBatch, Supervisor,
+};
+
+fn supervisor() -> Supervisor<Adapters> {
+    let mut config = RegisterBatch::new();
+    config.push(*Enable::default().set_pon().set_aen()).push(Atime(0xDB)).push(Wtime(0xF6));
+    config.push(*Control::default().write_ldrive(2));
+    Supervisor::new(Apds9960Drv::init(), config)
+}
+
+#[test]
+fn apply_enables_last() {
+    let mut stores = Vec::new();
+    let mut i2c = Trace::new(Apds9960Sim::new(), |transaction: &Transaction<'_>| {
+        stores.push(transaction.reg);
+    });
+    block_on(supervisor().apply(&mut i2c)).unwrap();
+    drop(i2c);
+    assert_eq!(stores, [0x81, 0x83, 0x8F, 0x80]);
+}
+
+#[test]
+fn check() {
+    let mut sim = Apds9960Sim::new();
+    let mut supervisor = supervisor();
+    block_on(supervisor.apply(&mut sim)).unwrap();
+    assert_eq!(block_on(supervisor.check(&mut sim)), Ok(None));
+    // A brown-out brings every register back to its reset value.
+    sim = Apds9960Sim::new();
+    let reset = DeviceReset { reg: 0x80, expected: 0x03, found: 0x00 };
+    assert_eq!(block_on(supervisor.check(&mut sim)), Ok(Some(reset)));
+    assert_eq!(supervisor.resets(), 1);
+    assert_eq!(
+        (sim.reg(0x80), sim.reg(0x81), sim.reg(0x83), sim.reg(0x8F)),
+        (0x03, 0xDB, 0xF6, 0x80)
+    );
+    assert_eq!(block_on(supervisor.check(&mut sim)), Ok(None));
+    sim.set_reg(0x8F, 0x00);
+    let reset = DeviceReset { reg: 0x8F, expected: 0x80, found: 0x00 };
+    assert_eq!(block_on(supervisor.check(&mut sim)), Ok(Some(reset)));
+    assert_eq!(sim.reg(0x8F), 0x80);
+    assert_eq!(supervisor.resets(), 2);
+}
+
+#[test]
+fn load_status() {
+    let mut sim = Apds9960Sim::new();
+    let mut supervisor = supervisor();
+    block_on(supervisor.apply(&mut sim)).unwrap();
+    sim.set_rgbc(1000, 100, 200, 300);
+    let (status, reset) = block_on(supervisor.load_status(&mut sim)).unwrap();
+    assert!(status.avalid());
+    assert_eq!(reset, None);
+    // Registers other than ENABLE aren't compared.
+    sim.set_reg(0x81, 0xFF);
+    assert_eq!(block_on(supervisor.load_status(&mut sim)).unwrap().1, None);
+    sim.set_reg(0x80, 0x00);
+    let reset = DeviceReset { reg: 0x80, expected: 0x03, found: 0x00 };
+    assert_eq!(block_on(supervisor.load_status(&mut sim)).unwrap().1, Some(reset));
+    assert_eq!((sim.reg(0x80), sim.reg(0x81)), (0x03, 0xDB));
+    assert_eq!(supervisor.resets(), 1);
+}
+
+#[test]
+fn self_clearing_bits() {
+    let mut sim = Apds9960Sim::new();
+    let mut config = RegisterBatch::new();
+    config.push(*Gconf4::default().set_gien().set_gmode().set_gfifo_clr());
+    let mut supervisor = Supervisor::new(Apds9960Drv::<Adapters>::init(), config);
+    block_on(supervisor.apply(&mut sim)).unwrap();
+    // GFIFO_CLR is never read back, and the device clears GMODE on its own.
+    sim.set_reg(0xAB, 0x02);
+    assert_eq!(block_on(supervisor.check(&mut sim)), Ok(None));
+    sim.set_reg(0xAB, 0x00);
+    let reset = DeviceReset { reg: 0xAB, expected: 0x07, found: 0x00 };
+    assert_eq!(block_on(supervisor.check(&mut sim)), Ok(Some(reset)));
+}
+
+#[test]
+fn store_updates_config() {
+    let mut sim = Apds9960Sim::new();
+    let mut supervisor = supervisor();
+    block_on(supervisor.apply(&mut sim)).unwrap();
+    block_on(supervisor.store(&mut sim, Atime(0xC0))).unwrap();
+    assert_eq!(block_on(supervisor.check(&mut sim)), Ok(None));
+    sim = Apds9960Sim::new();
+    assert!(block_on(supervisor.check(&mut sim)).unwrap().is_some());
+    assert_eq!(sim.reg(0x81), 0xC0);
+}
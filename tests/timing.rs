@@ -0,0 +1,73 @@
+use core::time::Duration;
+use smartoris_apds9960::timing::{self, Config, ALS_STEP, WAIT_STEP};
+
+fn close(a: Duration, b: Duration, tolerance: Duration) -> bool {
+    if a > b { a - b <= tolerance } else { b - a <= tolerance }
+}
+
+#[test]
+fn cycle_time_off() {
+    assert_eq!(Config::default().cycle_time().total(), Duration::default());
+}
+
+#[test]
+fn cycle_time_proximity_pulses() {
+    let mut config = Config::default();
+    config.enable.set_pon().set_pen();
+    config.ppulse.write_ppulse(7).write_pplen(2);
+    // 8 pulses of 16 µs, each followed by an equal off time.
+    assert_eq!(config.cycle_time().proximity, Duration::from_micros(8 * 16 * 2));
+}
+
+#[test]
+fn cycle_time_als_and_wait() {
+    let mut config = Config::default();
+    config.enable.set_pon().set_aen().set_wen();
+    config.atime = 0xDB;
+    config.wtime = 0xFF;
+    let cycle = config.cycle_time();
+    assert_eq!(cycle.als, ALS_STEP * 37);
+    assert_eq!(cycle.wait, WAIT_STEP);
+    config.config1.set_wlong();
+    assert_eq!(config.cycle_time().wait, WAIT_STEP * 12);
+}
+
+#[test]
+fn solve_none() {
+    assert!(timing::solve(Config::default(), None, None).is_none());
+    assert!(timing::solve(Config::default(), Some(Duration::from_micros(100)), None).is_none());
+}
+
+#[test]
+fn solve_short_wait() {
+    let config = Config { atime: 0xDB, ..Config::default() };
+    let period = Duration::from_millis(200);
+    let config = timing::solve(config, Some(period), None).unwrap();
+    assert!(config.enable.pon() && config.enable.aen() && config.enable.wen());
+    assert!(!config.enable.pen() && !config.config1.wlong());
+    assert_eq!(config.atime, 0xDB);
+    assert!(close(config.cycle_time().total(), period, WAIT_STEP));
+}
+
+#[test]
+fn solve_shortens_atime() {
+    let config = Config { atime: 0x00, ..Config::default() };
+    let period = Duration::from_millis(50);
+    let config = timing::solve(config, Some(period), Some(period)).unwrap();
+    assert!(config.enable.aen() && config.enable.pen());
+    assert!(config.atime > 0x00);
+    assert!(close(config.cycle_time().total(), period, WAIT_STEP));
+}
+
+#[test]
+fn solve_long_wait() {
+    let period = Duration::from_secs(8);
+    let config = timing::solve(Config::default(), None, Some(period)).unwrap();
+    assert!(config.config1.wlong());
+    assert!(close(config.cycle_time().total(), period, WAIT_STEP * 12));
+}
+
+#[test]
+fn solve_period_too_long() {
+    assert!(timing::solve(Config::default(), None, Some(Duration::from_secs(10))).is_none());
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "std")]
+
+use futures::executor::block_on;
+use smartoris_apds9960::{
+    adapters::{Adapters, Apds9960Sim, Operation, Trace, Transaction},
+    reg::Atime,
+    Apds9960Drv,
+};
+
+#[test]
+fn trace() {
+    let mut log = Vec::new();
+    let mut sim = Apds9960Sim::new();
+    sim.push_gesture([1, 2, 3, 4]);
+    let mut i2c = Trace::new(sim, |transaction: &Transaction<'_>| {
+        log.push((transaction.op, transaction.ok, transaction.to_string()));
+    });
+    let mut drv = Apds9960Drv::<Adapters>::init();
+    block_on(drv.store(&mut i2c, Atime(0xDB))).unwrap();
+    block_on(drv.load_id(&mut i2c)).unwrap();
+    block_on(drv.touch_ciclear(&mut i2c)).unwrap();
+    block_on(drv.drain_fifo(&mut i2c, 1)).unwrap();
+    i2c.port().fail_next(1);
+    block_on(drv.load_id(&mut i2c)).unwrap_err();
+    drop(i2c);
+    assert_eq!(log, [
+        (Operation::Store, true, "store ATIME (0x81) [DB]".to_string()),
+        (Operation::Load, true, "load ID (0x92) [AB]".to_string()),
+        (Operation::Touch, true, "touch CICLEAR (0xE6)".to_string()),
+        (Operation::DrainFifo, true, "drain GFIFO_U (0xFC) [01, 02, 03, 04]".to_string()),
+        (Operation::Load, false, "load ID (0x92) failed".to_string()),
+    ]);
+}
+
+#[test]
+fn display_unknown_register() {
+    let transaction =
+        Transaction { op: Operation::Load, reg: 0x82, name: None, bytes: &[], ok: true };
+    assert_eq!(transaction.to_string(), "load ? (0x82)");
+}
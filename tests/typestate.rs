@@ -0,0 +1,96 @@
+#![cfg(feature = "std")]
+
+use futures::executor::block_on;
+use smartoris_apds9960::{
+    adapters::{Adapters, Apds9960Sim, SimError},
+    Apds9960, Apds9960Drv, Off, On,
+};
+
+fn sensor(sim: &mut Apds9960Sim) -> Apds9960<Adapters, On> {
+    let sensor = Apds9960::<Adapters, Off>::new(Apds9960Drv::init());
+    block_on(sensor.power_on(sim)).ok().unwrap()
+}
+
+#[test]
+fn power_transitions() {
+    let mut sim = Apds9960Sim::new();
+    sim.set_reg(0x80, 0x0F);
+    let sensor = Apds9960::<Adapters, Off>::new(Apds9960Drv::init());
+    sim.fail_next(1);
+    let (sensor, err) = block_on(sensor.power_on(&mut sim)).err().unwrap();
+    assert_eq!(err, SimError::Nack);
+    assert_eq!(sim.reg(0x80), 0x0F);
+    let mut sensor = block_on(sensor.power_on(&mut sim)).ok().unwrap();
+    assert_eq!(sim.reg(0x80), 0x01);
+    block_on(sensor.enable_als(&mut sim)).unwrap();
+    assert_eq!(sim.reg(0x80), 0x03);
+    let sensor = block_on(sensor.power_off(&mut sim)).ok().unwrap();
+    assert_eq!(sim.reg(0x80), 0x00);
+    let mut sensor = block_on(sensor.power_on(&mut sim)).ok().unwrap();
+    assert!(sensor.als().is_none());
+    assert_eq!(sim.reg(0x80), 0x01);
+}
+
+#[test]
+fn als_engine() {
+    let mut sim = Apds9960Sim::new();
+    let mut sensor = sensor(&mut sim);
+    assert!(sensor.als().is_none());
+    let mut als = block_on(sensor.enable_als(&mut sim)).unwrap();
+    assert!(!block_on(als.is_valid(&mut sim)).unwrap());
+    sim.set_rgbc(1000, 100, 200, 300);
+    assert!(block_on(als.is_valid(&mut sim)).unwrap());
+    assert_eq!(block_on(als.load_rgbc(&mut sim)).unwrap().clear, 1000);
+    let als = sensor.als().unwrap();
+    block_on(als.disable(&mut sim)).unwrap();
+    assert_eq!(sim.reg(0x80), 0x01);
+    assert!(sensor.als().is_none());
+}
+
+#[test]
+fn proximity_and_gesture_share_pen() {
+    let mut sim = Apds9960Sim::new();
+    let mut sensor = sensor(&mut sim);
+    block_on(sensor.enable_proximity(&mut sim)).unwrap();
+    assert_eq!(sim.reg(0x80), 0x05);
+    block_on(sensor.enable_gesture(&mut sim)).unwrap();
+    assert_eq!(sim.reg(0x80), 0x45);
+    assert_eq!(sim.reg(0xAB) & 1, 1);
+    // PEN stays set for the gesture engine.
+    block_on(sensor.proximity().unwrap().disable(&mut sim)).unwrap();
+    assert_eq!(sim.reg(0x80), 0x45);
+    assert!(sensor.proximity().is_none());
+    assert!(sensor.gesture().is_some());
+    block_on(sensor.gesture().unwrap().disable(&mut sim)).unwrap();
+    assert_eq!(sim.reg(0x80), 0x01);
+    assert_eq!(sim.reg(0xAB) & 1, 0);
+    assert!(sensor.gesture().is_none());
+}
+
+#[test]
+fn gesture_keeps_proximity() {
+    let mut sim = Apds9960Sim::new();
+    let mut sensor = sensor(&mut sim);
+    block_on(sensor.enable_gesture(&mut sim)).unwrap();
+    assert!(sensor.proximity().is_none());
+    block_on(sensor.enable_proximity(&mut sim)).unwrap();
+    block_on(sensor.gesture().unwrap().disable(&mut sim)).unwrap();
+    assert_eq!(sim.reg(0x80), 0x05);
+    assert!(sensor.proximity().is_some());
+}
+
+#[test]
+fn gesture_engine() {
+    let mut sim = Apds9960Sim::new();
+    let mut sensor = sensor(&mut sim);
+    let mut gesture = block_on(sensor.enable_gesture(&mut sim)).unwrap();
+    sim.push_gesture([1, 2, 3, 4]);
+    sim.push_gesture([5, 6, 7, 8]);
+    assert_eq!(block_on(gesture.load_level(&mut sim)).unwrap(), 2);
+    assert_eq!(block_on(gesture.drain_fifo(&mut sim)).unwrap(), [1, 2, 3, 4, 5, 6, 7, 8]);
+    sim.push_gesture([1, 2, 3, 4]);
+    block_on(gesture.clear_fifo(&mut sim)).unwrap();
+    assert_eq!(block_on(gesture.load_level(&mut sim)).unwrap(), 0);
+    // Clearing the FIFO doesn't leave gesture mode.
+    assert_eq!(sim.reg(0xAB) & 1, 1);
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "std")]
+
+use async_trait::async_trait;
+use futures::executor::block_on;
+use smartoris_apds9960::{
+    adapters::{Adapters, Apds9960Sim, SimError},
+    reg::{Atime, Control, Wtime},
+    Apds9960Drv, Apds9960Error, Apds9960I2CPort, RegisterBatch,
+};
+
+/// A port with bits stuck at zero in one register.
+struct Stuck {
+    sim: Apds9960Sim,
+    reg: u8,
+    mask: u8,
+}
+
+#[async_trait]
+impl Apds9960I2CPort<Adapters> for Stuck {
+    type Error = SimError;
+
+    async fn write(
+        &mut self,
+        addr: u8,
+        mut buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, SimError)> {
+        let start = buf[0];
+        for (reg, byte) in (start..).zip(&mut buf[1..count]) {
+            if reg == self.reg {
+                *byte &= !self.mask;
+            }
+        }
+        self.sim.write(addr, buf, count).await
+    }
+
+    async fn read(
+        &mut self,
+        addr: u8,
+        buf: Box<[u8]>,
+        count: usize,
+    ) -> Result<Box<[u8]>, (Box<[u8]>, SimError)> {
+        self.sim.read(addr, buf, count).await
+    }
+}
+
+fn drv() -> Apds9960Drv<Adapters> {
+    Apds9960Drv::init()
+}
+
+fn stuck(reg: u8, mask: u8) -> Stuck {
+    Stuck { sim: Apds9960Sim::new(), reg, mask }
+}
+
+#[test]
+fn verified_accessors() {
+    let mut sim = Apds9960Sim::new();
+    let mut drv = drv();
+    let mut verified = drv.verified();
+    block_on(verified.store_atime(&mut sim, 0xDB)).unwrap();
+    block_on(verified.store_control(&mut sim, |r| r.write_ldrive(2))).unwrap();
+    block_on(verified.modify(&mut sim, |r: &mut Control| r.write_again(3))).unwrap();
+    assert_eq!(sim.reg(0x81), 0xDB);
+    assert_eq!(sim.reg(0x8F), 0x83);
+}
+
+#[test]
+fn verified_mismatch() {
+    let mut i2c = stuck(0x8F, 1 << 7);
+    let mut drv = drv();
+    block_on(drv.store_control(&mut i2c, |r| r.write_ldrive(2))).unwrap();
+    let result = block_on(drv.verified().store_control(&mut i2c, |r| r.write_ldrive(2)));
+    assert!(matches!(
+        result,
+        Err(Apds9960Error::VerifyFailed { reg: 0x8F, wrote: 0x80, read: 0x00 })
+    ));
+    let result = block_on(drv.verified().store_control(&mut i2c, |r| r.write_ldrive(1)));
+    assert!(result.is_ok());
+}
+
+#[test]
+fn verified_ignores_self_clearing_bits() {
+    // The simulator doesn't store GFIFO_CLR, and this port drops GMODE as
+    // the device does when it leaves the gesture engine.
+    let mut i2c = stuck(0xAB, 1 << 0);
+    let mut drv = drv();
+    let mut verified = drv.verified();
+    block_on(verified.store_gconf4(&mut i2c, |r| r.set_gfifo_clr().set_gmode())).unwrap();
+    assert_eq!(i2c.sim.reg(0xAB), 0);
+    let mut i2c = stuck(0xAB, 1 << 1);
+    let result = block_on(drv.verified().store_gconf4(&mut i2c, |r| r.set_gien()));
+    assert!(matches!(
+        result,
+        Err(Apds9960Error::VerifyFailed { reg: 0xAB, wrote: 0x02, read: 0x00 })
+    ));
+}
+
+#[test]
+fn verified_batch() {
+    let mut batch = RegisterBatch::new();
+    batch.push(Atime(0xDB)).push(Wtime(0xF6));
+    let mut i2c = stuck(0x83, 1 << 1);
+    let result = block_on(drv().verified().store_batch(&mut i2c, &batch));
+    assert!(matches!(
+        result,
+        Err(Apds9960Error::VerifyFailed { reg: 0x83, wrote: 0xF6, read: 0xF4 })
+    ));
+    // The first run was written and verified before the failure.
+    assert_eq!(i2c.sim.reg(0x81), 0xDB);
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "std")]
+
+use futures::executor::block_on;
+use smartoris_apds9960::{
+    adapters::{Adapters, Apds9960Sim, Operation, Trace, Transaction},
+    Apds9960Drv, Apds9960Error,
+};
+
+fn drv() -> Apds9960Drv<Adapters> {
+    Apds9960Drv::init()
+}
+
+#[test]
+fn set_als_window() {
+    let mut sim = Apds9960Sim::new();
+    sim.set_reg(0x93, 0x90);
+    block_on(drv().set_als_window(&mut sim, 0x1234, 0x5678, true)).unwrap();
+    assert_eq!([sim.reg(0x84), sim.reg(0x85)], [0x34, 0x12]);
+    assert_eq!([sim.reg(0x86), sim.reg(0x87)], [0x78, 0x56]);
+    assert_eq!(sim.reg(0x93), 0x00);
+}
+
+#[test]
+fn set_als_window_invalid() {
+    let mut sim = Apds9960Sim::new();
+    let result = block_on(drv().set_als_window(&mut sim, 2, 1, false));
+    assert_eq!(result, Err(Apds9960Error::InvalidWindow { low: 2, high: 1 }));
+    assert_eq!(sim.reg(0x84), 0);
+}
+
+/// Moves the proximity window from `old` to `new`, returning every window
+/// the device goes through.
+fn proximity_windows(old: (u8, u8), new: (u8, u8)) -> Vec<(u8, u8)> {
+    let mut sim = Apds9960Sim::new();
+    sim.set_reg(0x89, old.0);
+    sim.set_reg(0x8B, old.1);
+    let mut window = old;
+    let mut windows = Vec::new();
+    let mut i2c = Trace::new(sim, |transaction: &Transaction<'_>| {
+        if transaction.op == Operation::Store {
+            match transaction.reg {
+                0x89 => window.0 = transaction.bytes[0],
+                0x8B => window.1 = transaction.bytes[0],
+                _ => {}
+            }
+            windows.push(window);
+        }
+    });
+    block_on(drv().set_proximity_window(&mut i2c, new.0, new.1, false)).unwrap();
+    let (sim, _) = i2c.release();
+    assert_eq!((sim.reg(0x89), sim.reg(0x8B)), new);
+    windows
+}
+
+#[test]
+fn set_proximity_window_order() {
+    // Moving up: PIHT first, the intermediate window contains the old one.
+    assert_eq!(proximity_windows((10, 20), (30, 40)), [(10, 40), (30, 40)]);
+    assert_eq!(proximity_windows((0, 100), (50, 200)), [(0, 200), (50, 200)]);
+    // Moving down: PILT first, the intermediate window contains the old one.
+    assert_eq!(proximity_windows((30, 40), (10, 20)), [(10, 40), (10, 20)]);
+    // Shrinking: PILT first, the intermediate window contains the new one.
+    assert_eq!(proximity_windows((10, 40), (20, 30)), [(20, 40), (20, 30)]);
+    // Growing: PIHT first, the intermediate window contains the old one.
+    assert_eq!(proximity_windows((20, 30), (10, 40)), [(20, 40), (10, 40)]);
+}
+
+#[test]
+fn set_proximity_window_invalid() {
+    let mut sim = Apds9960Sim::new();
+    let result = block_on(drv().set_proximity_window(&mut sim, 2, 1, false));
+    assert_eq!(result, Err(Apds9960Error::InvalidWindow { low: 2, high: 1 }));
+}